@@ -0,0 +1,63 @@
+//! Expected answers, stored alongside each day's input
+//!
+//! `dayNN/answers.toml` looks like:
+//!
+//! ```toml
+//! part1 = "475"
+//! part2 = "279138"
+//! ```
+//!
+//! Either part may be omitted if its answer isn't known.
+
+use std::fs;
+use std::io;
+
+use toml::{Table, Value};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// The path at which a day's answers are stored
+pub fn path(day: u8) -> String {
+    format!("day{:02}/answers.toml", day)
+}
+
+impl Answers {
+    /// Load a day's answers, or `None` if none have been recorded
+    pub fn load(day: u8) -> Result<Option<Answers>, String> {
+        let path = path(day);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(format!("couldn't read {}: {}", path, err)),
+        };
+        let table = text
+            .parse::<Table>()
+            .map_err(|err| format!("couldn't parse {}: {}", path, err))?;
+        let part = |key: &str| match table.get(key) {
+            None => Ok(None),
+            Some(Value::String(answer)) => Ok(Some(answer.clone())),
+            Some(_) => Err(format!("{}: {} must be a string", path, key)),
+        };
+        Ok(Some(Answers {
+            part1: part("part1")?,
+            part2: part("part2")?,
+        }))
+    }
+
+    /// Store these as a day's answers, replacing any already recorded
+    pub fn save(&self, day: u8) -> Result<(), String> {
+        let mut table = Table::new();
+        if let Some(ref answer) = self.part1 {
+            table.insert("part1".to_string(), Value::String(answer.clone()));
+        }
+        if let Some(ref answer) = self.part2 {
+            table.insert("part2".to_string(), Value::String(answer.clone()));
+        }
+        let path = path(day);
+        fs::write(&path, table.to_string()).map_err(|err| format!("couldn't write {}: {}", path, err))
+    }
+}
@@ -0,0 +1,104 @@
+//! In-process benchmarks of each day's parse, part 1, and part 2
+//!
+//! Each phase is timed separately and repeatedly, excluding process startup
+//! and reading the input file. Part timings reuse a single parsed input.
+
+use std::fs::File;
+use std::hint::black_box;
+use std::io::{self, BufWriter, Write};
+use std::time::{Duration, Instant};
+
+use util::{Error, Solution};
+
+/// How long to spend measuring each phase
+pub struct Budget {
+    /// Keep sampling until this much time has been spent...
+    pub target: Duration,
+    /// ...but take at least this many samples
+    pub min_samples: usize,
+    /// ...and at most this many
+    pub max_samples: usize,
+}
+
+impl Default for Budget {
+    fn default() -> Budget {
+        Budget {
+            target: Duration::from_secs(1),
+            min_samples: 3,
+            max_samples: 1000,
+        }
+    }
+}
+
+/// Summary statistics of a phase's samples
+pub struct Stats {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+pub struct Benchmarks {
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+fn measure<T, F: FnMut() -> T>(budget: &Budget, mut f: F) -> Stats {
+    // warm up caches and lazy allocations
+    black_box(f());
+
+    let mut samples = Vec::new();
+    let mut total = Duration::default();
+    while samples.len() < budget.max_samples
+        && (samples.len() < budget.min_samples || total < budget.target)
+    {
+        let start = Instant::now();
+        black_box(f());
+        let elapsed = start.elapsed();
+        samples.push(elapsed);
+        total += elapsed;
+    }
+
+    samples.sort();
+    Stats {
+        samples: samples.len(),
+        min: samples[0],
+        median: samples[samples.len() / 2],
+        mean: total / samples.len() as u32,
+    }
+}
+
+pub fn bench<S: Solution>(input: &str, budget: &Budget) -> Result<Benchmarks, Error> {
+    let parsed = S::parse(input)?;
+    Ok(Benchmarks {
+        parse: measure(budget, || S::parse(input)),
+        part1: measure(budget, || S::part1(&parsed)),
+        part2: measure(budget, || S::part2(&parsed)),
+    })
+}
+
+/// Write results as CSV, one row per day and phase, with times in nanoseconds
+pub fn write_csv(path: &str, results: &[(u8, Benchmarks)]) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "day,phase,samples,min_ns,median_ns,mean_ns")?;
+    for (day, benchmarks) in results {
+        for (phase, stats) in [
+            ("parse", &benchmarks.parse),
+            ("part1", &benchmarks.part1),
+            ("part2", &benchmarks.part2),
+        ] {
+            writeln!(
+                out,
+                "{},{},{},{},{},{}",
+                day,
+                phase,
+                stats.samples,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+            )?;
+        }
+    }
+    out.flush()
+}
@@ -0,0 +1,91 @@
+//! The table of every day's `Solution`, erased to a common type
+//!
+//! Each day parses its input into a different type, so the runner
+//! can't hold the solutions directly. Instead, each entry is the
+//! generic `solve` and `bench` functions instantiated for that day.
+
+use std::time::{Duration, Instant};
+
+use util::{Error, Solution};
+
+use bench::{bench, Benchmarks, Budget};
+
+/// A single part's answer, and how long it took to compute
+pub struct Answer {
+    /// `None` if the part has no solution for this input
+    pub value: Option<String>,
+    pub elapsed: Duration,
+}
+
+/// Everything learned by running a day
+pub struct Outcome {
+    pub parse: Duration,
+    pub part1: Answer,
+    pub part2: Answer,
+}
+
+pub struct Day {
+    pub number: u8,
+    pub solve: fn(&str) -> Result<Outcome, Error>,
+    pub bench: fn(&str, &Budget) -> Result<Benchmarks, Error>,
+}
+
+fn time<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+    let start = Instant::now();
+    let value = f();
+    (value, start.elapsed())
+}
+
+fn solve<S: Solution>(input: &str) -> Result<Outcome, Error> {
+    let (parsed, parse) = time(|| S::parse(input));
+    let parsed = parsed?;
+    let (value, elapsed) = time(|| S::part1(&parsed).map(|a| a.to_string()));
+    let part1 = Answer { value, elapsed };
+    let (value, elapsed) = time(|| S::part2(&parsed).map(|a| a.to_string()));
+    let part2 = Answer { value, elapsed };
+    Ok(Outcome {
+        parse,
+        part1,
+        part2,
+    })
+}
+
+macro_rules! day {
+    ($number:expr, $solution:ty) => {
+        Day {
+            number: $number,
+            solve: solve::<$solution>,
+            bench: bench::<$solution>,
+        }
+    };
+}
+
+pub fn days() -> Vec<Day> {
+    vec![
+        day!(1, day01::Day01),
+        day!(2, day02::Day02),
+        day!(3, day03::Day03),
+        day!(4, day04::Day04),
+        day!(5, day05::Day05),
+        day!(6, day06::Day06),
+        day!(7, day07::Day07),
+        day!(8, day08::Day08),
+        day!(9, day09::Day09),
+        day!(10, day10::Day10),
+        day!(11, day11::Day11),
+        day!(12, day12::Day12),
+        day!(13, day13::Day13),
+        day!(14, day14::Day14),
+        day!(15, day15::Day15),
+        day!(16, day16::Day16),
+        day!(17, day17::Day17),
+        day!(18, day18::Day18),
+        day!(19, day19::Day19),
+        day!(20, day20::Day20),
+        day!(21, day21::Day21),
+        day!(22, day22::Day22),
+        day!(23, day23::Day23),
+        day!(24, day24::Day24),
+        day!(25, day25::Day25),
+    ]
+}
@@ -0,0 +1,295 @@
+//! Run any or all of the Advent of Code 2017 solutions
+//!
+//! ```text
+//! aoc run 18      # a single day
+//! aoc run 3..10   # an inclusive range of days
+//! aoc run all     # every day
+//! aoc verify all  # check every day against its recorded answers
+//! aoc record 18   # store a day's current answers as the expected ones
+//! aoc bench all   # time each phase of every day, writing bench.csv
+//! ```
+//!
+//! Inputs are read from `dayNN/input.txt`, and expected answers from
+//! `dayNN/answers.toml`, relative to the current directory.
+
+extern crate toml;
+
+extern crate util;
+
+extern crate day01;
+extern crate day02;
+extern crate day03;
+extern crate day04;
+extern crate day05;
+extern crate day06;
+extern crate day07;
+extern crate day08;
+extern crate day09;
+extern crate day10;
+extern crate day11;
+extern crate day12;
+extern crate day13;
+extern crate day14;
+extern crate day15;
+extern crate day16;
+extern crate day17;
+extern crate day18;
+extern crate day19;
+extern crate day20;
+extern crate day21;
+extern crate day22;
+extern crate day23;
+extern crate day24;
+extern crate day25;
+
+use std::env;
+use std::process;
+
+mod answers;
+mod bench;
+mod days;
+use answers::Answers;
+use bench::Budget;
+use days::{days, Answer, Day, Outcome};
+
+const USAGE: &str = "usage: aoc <COMMAND> <DAYS>
+       aoc bench <DAYS> [OUTPUT]
+
+COMMAND may be:
+  run      solve each day, reporting answers and timings
+  verify   solve each day, reporting answers which differ from those recorded
+  record   solve each day, recording its answers as the expected ones
+  bench    time each day's parse, part 1, and part 2, writing the results
+           as CSV to OUTPUT (default: bench.csv)
+
+DAYS may be:
+  a single day:           18
+  an inclusive range:     3..10
+  every day:              all";
+
+/// Parse a specification of which days to run into an inclusive range
+fn parse_days(spec: &str) -> Result<(u8, u8), String> {
+    let parse_day = |day: &str| -> Result<u8, String> {
+        match day.trim().parse() {
+            Ok(n) if (1..=25).contains(&n) => Ok(n),
+            _ => Err(format!("not a day between 1 and 25: {:?}", day)),
+        }
+    };
+    if spec == "all" {
+        Ok((1, 25))
+    } else if let Some(idx) = spec.find("..") {
+        let (low, high) = (&spec[..idx], &spec[idx + 2..]);
+        let high = high.strip_prefix('=').unwrap_or(high);
+        let (low, high) = (parse_day(low)?, parse_day(high)?);
+        if low > high {
+            return Err(format!("empty range of days: {}", spec));
+        }
+        Ok((low, high))
+    } else {
+        parse_day(spec).map(|day| (day, day))
+    }
+}
+
+fn report(name: &str, answer: &Answer) {
+    match answer.value {
+        Some(ref value) => println!("  {}: {} ({:?})", name, value, answer.elapsed),
+        None => println!("  {}: unsolved", name),
+    }
+}
+
+fn read_input(day: &Day) -> Result<String, String> {
+    let path = format!("day{:02}/input.txt", day.number);
+    util::read_file(&path).map_err(|err| format!("couldn't read {}: {}", path, err))
+}
+
+/// Read a day's input and solve it
+fn solve(day: &Day) -> Result<Outcome, String> {
+    (day.solve)(&read_input(day)?).map_err(|err| err.to_string())
+}
+
+fn run_day(day: &Day) -> bool {
+    println!("day {:02}:", day.number);
+    let input = match read_input(day) {
+        Ok(input) => input,
+        Err(err) => {
+            println!("  skipped: {}", err);
+            return true;
+        }
+    };
+    match (day.solve)(&input) {
+        Ok(outcome) => {
+            println!("  parse: {:?}", outcome.parse);
+            report("part 1", &outcome.part1);
+            report("part 2", &outcome.part2);
+            true
+        }
+        Err(err) => {
+            println!("  failed: {}", err);
+            false
+        }
+    }
+}
+
+/// Compare an answer with the expected one, if any
+///
+/// Returns a description of the regression, if the answer changed.
+fn check(name: &str, expected: &Option<String>, answer: &Answer) -> Option<String> {
+    let expected = expected.as_ref()?;
+    match answer.value {
+        Some(ref value) if value == expected => None,
+        Some(ref value) => Some(format!("{}: expected {}, got {}", name, expected, value)),
+        None => Some(format!("{}: expected {}, got nothing", name, expected)),
+    }
+}
+
+/// Returns `false` if the day regressed
+fn verify_day(day: &Day) -> bool {
+    let expected = match Answers::load(day.number) {
+        Ok(Some(expected)) => expected,
+        Ok(None) => {
+            println!("day {:02}: no recorded answers", day.number);
+            return true;
+        }
+        Err(err) => {
+            println!("day {:02}: FAILED: {}", day.number, err);
+            return false;
+        }
+    };
+    let outcome = match solve(day) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            println!("day {:02}: FAILED: {}", day.number, err);
+            return false;
+        }
+    };
+    let regressions = [
+        check("part 1", &expected.part1, &outcome.part1),
+        check("part 2", &expected.part2, &outcome.part2),
+    ];
+    if regressions.iter().all(Option::is_none) {
+        println!("day {:02}: ok", day.number);
+        return true;
+    }
+    println!("day {:02}: REGRESSION", day.number);
+    for regression in regressions.iter().flatten() {
+        println!("  {}", regression);
+    }
+    false
+}
+
+/// Returns `false` if the day's answers couldn't be recorded
+fn record_day(day: &Day) -> bool {
+    let outcome = match solve(day) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            println!("day {:02}: FAILED: {}", day.number, err);
+            return false;
+        }
+    };
+    let answers = Answers {
+        part1: outcome.part1.value,
+        part2: outcome.part2.value,
+    };
+    match answers.save(day.number) {
+        Ok(()) => {
+            println!("day {:02}: recorded {}", day.number, answers::path(day.number));
+            true
+        }
+        Err(err) => {
+            println!("day {:02}: FAILED: {}", day.number, err);
+            false
+        }
+    }
+}
+
+/// Returns `false` if any day failed, or the results couldn't be written
+///
+/// The results of the other days are still written when one fails.
+fn bench_days(days: &[&Day], output: &str) -> bool {
+    let budget = Budget::default();
+    let mut results = Vec::new();
+    let mut succeeded = true;
+    for day in days {
+        println!("day {:02}:", day.number);
+        let input = match read_input(day) {
+            Ok(input) => input,
+            Err(err) => {
+                println!("  skipped: {}", err);
+                continue;
+            }
+        };
+        match (day.bench)(&input, &budget) {
+            Ok(benchmarks) => {
+                for &(name, stats) in &[
+                    ("parse", &benchmarks.parse),
+                    ("part 1", &benchmarks.part1),
+                    ("part 2", &benchmarks.part2),
+                ] {
+                    println!(
+                        "  {}: median {:?}, min {:?} ({} samples)",
+                        name, stats.median, stats.min, stats.samples
+                    );
+                }
+                results.push((day.number, benchmarks));
+            }
+            Err(err) => {
+                println!("  failed: {}", err);
+                succeeded = false;
+            }
+        }
+    }
+    match bench::write_csv(output, &results) {
+        Ok(()) => {
+            println!("wrote {}", output);
+            succeeded
+        }
+        Err(err) => {
+            println!("couldn't write {}: {}", output, err);
+            false
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command: Option<fn(&Day) -> bool> = match args.first().map(String::as_str) {
+        Some("run") => Some(run_day),
+        Some("verify") => Some(verify_day),
+        Some("record") => Some(record_day),
+        Some("bench") => None,
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(1);
+        }
+    };
+    let max_args = if command.is_some() { 2 } else { 3 };
+    let (low, high) = match args.get(1) {
+        Some(spec) if args.len() <= max_args => parse_days(spec).unwrap_or_else(|err| {
+            eprintln!("{}\n\n{}", err, USAGE);
+            process::exit(1);
+        }),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(1);
+        }
+    };
+
+    let days = days();
+    let days: Vec<&Day> = days
+        .iter()
+        .filter(|day| day.number >= low && day.number <= high)
+        .collect();
+    let succeeded = match command {
+        Some(command) => {
+            let mut succeeded = true;
+            for day in &days {
+                succeeded &= command(day);
+            }
+            succeeded
+        }
+        None => bench_days(&days, args.get(2).map_or("bench.csv", String::as_str)),
+    };
+    if !succeeded {
+        process::exit(1);
+    }
+}
@@ -1,7 +1,11 @@
-pub fn captcha(digits: &[usize]) -> usize {
+extern crate util;
+use util::{Error, Solution};
+
+/// Sum the digits which match the digit `offset` places further around the circular list
+pub fn captcha_offset(digits: &[usize], offset: usize) -> usize {
     let mut sum = 0;
     for index in 0..digits.len() {
-        let index_next = (index + (digits.len() / 2)) % digits.len();
+        let index_next = (index + offset) % digits.len();
         if digits[index] == digits[index_next] {
             sum += digits[index];
         }
@@ -9,6 +13,10 @@ pub fn captcha(digits: &[usize]) -> usize {
     sum
 }
 
+pub fn captcha(digits: &[usize]) -> usize {
+    captcha_offset(digits, digits.len() / 2)
+}
+
 pub fn captcha_str(input: &str) -> Option<usize> {
     input
         .chars()
@@ -16,3 +24,31 @@ pub fn captcha_str(input: &str) -> Option<usize> {
         .collect::<Option<Vec<usize>>>()
         .map(|digits| captcha(&digits))
 }
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input<'a> = Vec<usize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<usize>, Error> {
+        input
+            .trim()
+            .chars()
+            .map(|c| {
+                c.to_digit(10)
+                    .map(|d| d as usize)
+                    .ok_or_else(|| Error::parse(format!("not a digit: {:?}", c)))
+            })
+            .collect()
+    }
+
+    fn part1(digits: &Vec<usize>) -> Option<usize> {
+        Some(captcha_offset(digits, 1))
+    }
+
+    fn part2(digits: &Vec<usize>) -> Option<usize> {
+        Some(captcha(digits))
+    }
+}
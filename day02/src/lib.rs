@@ -25,6 +25,9 @@ use std::iter::Sum;
 extern crate num_traits;
 use num_traits::Zero;
 
+extern crate util;
+use util::{str_as, Error, Solution};
+
 pub fn checksum<X, Y, T>(sheet: &Y) -> T
 where
     Y: AsRef<[X]>,
@@ -52,10 +55,10 @@ where
     let row = row.as_ref();
     for divisor_index in 0..row.len() {
         for dividend_index in 0..row.len() {
-            if dividend_index != divisor_index {
-                if row[divisor_index] % row[dividend_index] == T::zero() {
-                    return row[divisor_index] / row[dividend_index];
-                }
+            if dividend_index != divisor_index &&
+                row[divisor_index] % row[dividend_index] == T::zero()
+            {
+                return row[divisor_index] / row[dividend_index];
             }
         }
     }
@@ -70,3 +73,23 @@ where
 {
     sheet.as_ref().iter().map(even_division).sum()
 }
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input<'a> = Vec<Vec<u64>>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Vec<Vec<u64>>, Error> {
+        str_as(input)
+    }
+
+    fn part1(sheet: &Vec<Vec<u64>>) -> Option<u64> {
+        Some(checksum(sheet))
+    }
+
+    fn part2(sheet: &Vec<Vec<u64>>) -> Option<u64> {
+        Some(divisible_checksum(sheet))
+    }
+}
@@ -20,6 +20,9 @@
 
 use std::cmp::{max, min};
 
+extern crate util;
+use util::{Error, Solution};
+
 struct SpiralMemoryRings {
     ring_maxes: Vec<usize>,
 }
@@ -72,7 +75,7 @@ impl SpiralMemoryRings {
         for idx in 1..4 {
             values[idx] = values[idx - 1] + (2 * ring)
         }
-        assert!(!values.iter().any(|&v| v == 0));
+        assert!(!values.contains(&0));
         values
     }
 
@@ -119,8 +122,8 @@ impl SpiralMemory {
         let origin = (rings, rings);
         memory[origin.1][origin.0] = Some(1);
         SpiralMemory {
-            origin: origin,
-            memory: memory,
+            origin,
+            memory,
         }
     }
 }
@@ -150,22 +153,22 @@ impl StressTest {
             (self.spiral_memory.origin.0 + 1, self.spiral_memory.origin.1)
         } else {
             if self.spiral_memory.memory[point.1][point.0 - 1].is_some() &&
-                !self.spiral_memory.memory[point.1 - 1][point.0].is_some()
+                self.spiral_memory.memory[point.1 - 1][point.0].is_none()
             {
                 // there's something west of us and nothing north, go north
                 (point.0, point.1 - 1)
             } else if self.spiral_memory.memory[point.1 + 1][point.0].is_some() &&
-                       !self.spiral_memory.memory[point.1][point.0 - 1].is_some()
+                       self.spiral_memory.memory[point.1][point.0 - 1].is_none()
             {
                 // something south of us, go west
                 (point.0 - 1, point.1)
             } else if self.spiral_memory.memory[point.1][point.0 + 1].is_some() &&
-                       !self.spiral_memory.memory[point.1 + 1][point.0].is_some()
+                       self.spiral_memory.memory[point.1 + 1][point.0].is_none()
             {
                 // something east of us, go south
                 (point.0, point.1 + 1)
             } else if self.spiral_memory.memory[point.1 - 1][point.0].is_some() &&
-                       !self.spiral_memory.memory[point.1][point.0 + 1].is_some()
+                       self.spiral_memory.memory[point.1][point.0 + 1].is_none()
             {
                 // something north of us, go east
                 (point.0 + 1, point.1)
@@ -213,3 +216,27 @@ impl StressTest {
         rv
     }
 }
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input<'a> = usize;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<usize, Error> {
+        match input.trim().parse() {
+            Ok(0) => Err(Error::parse("square 0 doesn't exist")),
+            Ok(val) => Ok(val),
+            Err(e) => Err(Error::parse(e)),
+        }
+    }
+
+    fn part1(&val: &usize) -> Option<usize> {
+        Some(steps_to_origin(val))
+    }
+
+    fn part2(&val: &usize) -> Option<usize> {
+        Some(StressTest::new(val).first_cell_greater_than())
+    }
+}
@@ -1,6 +1,9 @@
 extern crate counter;
 use counter::Counter;
 
+extern crate util;
+use util::{tokenize, Error, Solution, Tokenized};
+
 use std::hash::Hash;
 
 pub fn contains_only_unique<S, T>(phrase: &S) -> bool
@@ -58,3 +61,23 @@ where
 {
     list.as_ref().iter().filter(is_valid).count()
 }
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input<'a> = Tokenized<&'a str>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Tokenized<&str>, Error> {
+        Ok(tokenize(input))
+    }
+
+    fn part1(passphrases: &Tokenized<&str>) -> Option<usize> {
+        Some(count_unique(passphrases))
+    }
+
+    fn part2(passphrases: &Tokenized<&str>) -> Option<usize> {
+        Some(count_valid(passphrases))
+    }
+}
@@ -1,3 +1,6 @@
+extern crate util;
+use util::{flatten, str_as, Error, Solution};
+
 pub struct JumpMemory {
     values: Vec<isize>,
     cursor: usize,
@@ -70,3 +73,23 @@ impl JumpMemory {
         self.steps
     }
 }
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input<'a> = Vec<isize>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<isize>, Error> {
+        str_as(input).map(flatten)
+    }
+
+    fn part1(jumps: &Vec<isize>) -> Option<usize> {
+        Some(JumpMemory::new(jumps).run())
+    }
+
+    fn part2(jumps: &Vec<isize>) -> Option<usize> {
+        Some(JumpMemory::new(jumps).run2())
+    }
+}
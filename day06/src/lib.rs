@@ -2,6 +2,9 @@ use std::collections::HashMap;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
+extern crate util;
+use util::{str_as, Error, Solution};
+
 pub type Banks = Vec<usize>;
 
 fn first_index_of_max_val(banks: &Banks) -> Option<usize> {
@@ -58,3 +61,26 @@ pub fn redistributions_cycle_len(banks: &Banks) -> (usize, usize) {
         cycle_len - state_hashes.get(&hash_of(&banks)).unwrap(),
     )
 }
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input<'a> = Banks;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Banks, Error> {
+        str_as(input)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::parse("no memory banks in input"))
+    }
+
+    fn part1(banks: &Banks) -> Option<usize> {
+        Some(redistributions_cycle_len(banks).0)
+    }
+
+    fn part2(banks: &Banks) -> Option<usize> {
+        Some(redistributions_cycle_len(banks).1)
+    }
+}
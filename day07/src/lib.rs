@@ -2,8 +2,15 @@ use std::collections::HashMap;
 use std::rc::{Rc, Weak};
 use std::cell::RefCell;
 
+extern crate util;
+use util::{Error, Solution};
+
+#[allow(warnings, clippy::all)]
 pub mod parser; // synthesized by lalrpop
 
+/// Unbalanced nodes, each with its children's `(name, weight, total_weight)`
+pub type UnbalancedSiblings<'a> = Vec<(&'a str, Vec<(&'a str, usize, usize)>)>;
+
 #[derive(Debug, Clone)]
 pub struct Node<'a> {
     pub name: &'a str,
@@ -15,24 +22,39 @@ pub struct Node<'a> {
 impl<'a> Node<'a> {
     fn new(name: &'a str, weight: usize, carried_len: usize) -> Node<'a> {
         Node {
-            name: name,
-            weight: weight,
+            name,
+            weight,
             children: RefCell::new(Vec::with_capacity(carried_len)),
             parent: RefCell::new(None),
         }
     }
 
     pub fn from_input(input_contents: &'a str) -> Vec<Rc<Node<'a>>> {
+        Node::try_from_input(input_contents).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_input(input_contents: &'a str) -> Result<Vec<Rc<Node<'a>>>, Error> {
         let mut nodes = HashMap::new();
         let mut carried_map = HashMap::new();
 
         // start by creating nodes for every line in the input
-        for line in input_contents.lines() {
+        for line in input_contents.lines().filter(|line| !line.trim().is_empty()) {
             if let Ok((name, weight, carried)) = parser::parse_Node(line) {
                 nodes.insert(name, Rc::new(Node::new(name, weight, carried.len())));
                 carried_map.insert(name, carried);
             } else {
-                panic!("Failed to parse {}", line);
+                return Err(Error::parse(format!("Failed to parse {}", line)));
+            }
+        }
+
+        // every carried node must exist before we can link them up
+        for carried_names in carried_map.values() {
+            for child_name in carried_names.iter() {
+                if !nodes.contains_key(child_name) {
+                    return Err(Error::parse(
+                        format!("Carried node {} is not in nodes list", child_name),
+                    ));
+                }
             }
         }
 
@@ -68,7 +90,7 @@ impl<'a> Node<'a> {
 
         let mut rv = Vec::with_capacity(nodes.len());
         rv.extend(nodes.drain().map(|(_, node_ref)| node_ref));
-        rv
+        Ok(rv)
     }
 
     pub fn parent_exists(&self) -> bool {
@@ -101,7 +123,7 @@ impl<'a> Node<'a> {
             }
     }
 
-    fn unbalanced_siblings_interior(&self, rv: &mut Vec<(&'a str, Vec<(&'a str, usize, usize)>)>) {
+    fn unbalanced_siblings_interior(&self, rv: &mut UnbalancedSiblings<'a>) {
         if self.children_are_unbalanced() {
             rv.push((
                 self.name,
@@ -117,11 +139,66 @@ impl<'a> Node<'a> {
         }
     }
 
-    pub fn unbalanced_siblings(&self) -> Vec<(&'a str, Vec<(&'a str, usize, usize)>)> {
+    pub fn unbalanced_siblings(&self) -> UnbalancedSiblings<'a> {
         let mut rv = Vec::new();
         self.unbalanced_siblings_interior(&mut rv);
         rv
     }
+
+    /// Find the weight the single wrong node in this tower would need to balance it
+    ///
+    /// The deepest unbalanced node is the one whose child is wrong: everything above
+    /// it is only unbalanced because it carries that child.
+    pub fn corrected_weight(&self) -> Option<usize> {
+        let unbalanced_siblings = self.unbalanced_siblings();
+        let (_, children) = unbalanced_siblings.last()?;
+        let total_weight_count = |total_weight| {
+            children
+                .iter()
+                .filter(|&&(_, _, tw)| tw == total_weight)
+                .count()
+        };
+        let &(_, weight, wrong_total) = children
+            .iter()
+            .find(|&&(_, _, total_weight)| total_weight_count(total_weight) == 1)?;
+        let &(_, _, right_total) = children
+            .iter()
+            .find(|&&(_, _, total_weight)| total_weight != wrong_total)?;
+        Some(weight + right_total - wrong_total)
+    }
+}
+
+/// Find the single node which has no parent
+pub fn root<'a, 'n>(nodes: &'n [Rc<Node<'a>>]) -> Option<&'n Rc<Node<'a>>> {
+    let mut roots = nodes.iter().filter(|node| !node.parent_exists());
+    match (roots.next(), roots.next()) {
+        (Some(root), None) => Some(root),
+        _ => None,
+    }
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input<'a> = Vec<Rc<Node<'a>>>;
+    type Answer1 = String;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<Rc<Node<'_>>>, Error> {
+        let nodes = Node::try_from_input(input)?;
+        if root(&nodes).is_none() {
+            return Err(Error::parse("input must contain exactly one root node"));
+        }
+        Ok(nodes)
+    }
+
+    fn part1(nodes: &Vec<Rc<Node<'_>>>) -> Option<String> {
+        root(nodes).map(|root| root.name.to_string())
+    }
+
+    fn part2(nodes: &Vec<Rc<Node<'_>>>) -> Option<usize> {
+        root(nodes)?.corrected_weight()
+    }
 }
 
 #[cfg(test)]
@@ -160,11 +237,7 @@ mod tests {
         let nodes = Node::from_input(example());
         // ensure there is exactly one root node
         assert_eq!(nodes.iter().filter(|node| !node.parent_exists()).count(), 1);
-        let root = nodes
-            .iter()
-            .filter(|node| !node.parent_exists())
-            .next()
-            .unwrap();
+        let root = nodes.iter().find(|node| !node.parent_exists()).unwrap();
         // ensure the correct node is detected
         assert_eq!(root.name, "tknk");
         assert_eq!(root.get_weight(), 41);
@@ -177,11 +250,7 @@ mod tests {
         let nodes = Node::from_input(example());
         // ensure there is exactly one root node
         assert_eq!(nodes.iter().filter(|node| !node.parent_exists()).count(), 1);
-        let root = nodes
-            .iter()
-            .filter(|node| !node.parent_exists())
-            .next()
-            .unwrap();
+        let root = nodes.iter().find(|node| !node.parent_exists()).unwrap();
         // ensure the correct node is detected
         assert_eq!(root.name, "tknk");
     }
@@ -191,25 +260,22 @@ mod tests {
         let nodes = Node::from_input(example());
         // ensure there is exactly one root node
         assert_eq!(nodes.iter().filter(|node| !node.parent_exists()).count(), 1);
-        let root = nodes
-            .iter()
-            .filter(|node| !node.parent_exists())
-            .next()
-            .unwrap();
+        let root = nodes.iter().find(|node| !node.parent_exists()).unwrap();
 
         let unbalanced_siblings = root.unbalanced_siblings();
-        for &(ref unbalanced_node, ref children) in unbalanced_siblings.iter() {
+        for (unbalanced_node, children) in unbalanced_siblings.iter() {
             println!(
                 "Found unbalanced node {} with child weights:",
                 unbalanced_node
             );
-            for &(ref name, ref weight, ref total_weight) in children {
+            for (name, weight, total_weight) in children {
                 println!("  {}: {} ({})", name, weight, total_weight);
             }
         }
 
         assert_eq!(unbalanced_siblings.len(), 1);
         assert_eq!(unbalanced_siblings[0].0, "tknk");
+        assert_eq!(root.corrected_weight(), Some(60));
     }
 
     #[test]
@@ -218,21 +284,17 @@ mod tests {
         let nodes = Node::from_input(&biggy);
         // ensure there is exactly one root node
         assert_eq!(nodes.iter().filter(|node| !node.parent_exists()).count(), 1);
-        let root = nodes
-            .iter()
-            .filter(|node| !node.parent_exists())
-            .next()
-            .unwrap();
+        let root = nodes.iter().find(|node| !node.parent_exists()).unwrap();
 
         assert_eq!(root.name, "blah");
 
         let unbalanced_siblings = root.unbalanced_siblings();
-        for &(ref unbalanced_node, ref children) in unbalanced_siblings.iter() {
+        for (unbalanced_node, children) in unbalanced_siblings.iter() {
             println!(
                 "Found unbalanced node {} with child weights:",
                 unbalanced_node
             );
-            for &(ref name, ref weight, ref total_weight) in children {
+            for (name, weight, total_weight) in children {
                 println!("  {}: {} ({})", name, weight, total_weight);
             }
         }
@@ -0,0 +1,345 @@
+//! Run programs with register names interned to dense indices
+//!
+//! Compiling a program assigns each distinct register name an index, in order
+//! of first appearance, so that executing it only needs a `Vec` of values:
+//! nothing is hashed or allocated per instruction.
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+
+use observer::{Highest, Observer, Peak, Record};
+use {Action, Comparison, Condition, Operand, Operation, Program, Register};
+
+/// A resolved `Operand`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    Slot(usize),
+    Immediate(Register),
+}
+
+impl Value {
+    #[inline]
+    fn get(&self, values: &[Register]) -> Register {
+        match *self {
+            Value::Slot(slot) => values[slot],
+            Value::Immediate(value) => value,
+        }
+    }
+}
+
+/// A resolved `Action`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Effect {
+    register: usize,
+    operation: Operation,
+    value: Value,
+}
+
+impl Effect {
+    /// Apply this effect, returning `false` on division by zero
+    ///
+    /// Arithmetic wraps on overflow.
+    #[inline]
+    fn apply(&self, values: &mut [Register]) -> bool {
+        let operand = self.value.get(values);
+        let current = values[self.register];
+        values[self.register] = match self.operation {
+            Operation::Inc => current.wrapping_add(operand),
+            Operation::Dec => current.wrapping_sub(operand),
+            Operation::Mul => current.wrapping_mul(operand),
+            Operation::Div if operand == 0 => return false,
+            Operation::Div => current.wrapping_div(operand),
+            Operation::Set => operand,
+        };
+        true
+    }
+}
+
+/// A resolved `Condition`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Test {
+    Compare(Value, Comparison, Value),
+    And(Box<Test>, Box<Test>),
+    Or(Box<Test>, Box<Test>),
+}
+
+impl Test {
+    fn holds(&self, values: &[Register]) -> bool {
+        match *self {
+            Test::Compare(left, comparison, right) => {
+                comparison.test(left.get(values), right.get(values))
+            }
+            Test::And(ref left, ref right) => left.holds(values) && right.holds(values),
+            Test::Or(ref left, ref right) => left.holds(values) || right.holds(values),
+        }
+    }
+}
+
+/// A single compiled instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Code {
+    effect: Effect,
+    test: Test,
+    otherwise: Option<Effect>,
+}
+
+impl Code {
+    /// Run this instruction, returning the register it wrote, if any
+    #[inline]
+    fn process(
+        &self,
+        index: usize,
+        values: &mut [Register],
+    ) -> Result<Option<usize>, DivideByZero> {
+        let effect = if self.test.holds(values) {
+            &self.effect
+        } else {
+            match self.otherwise {
+                Some(ref effect) => effect,
+                None => return Ok(None),
+            }
+        };
+        if effect.apply(values) {
+            Ok(Some(effect.register))
+        } else {
+            Err(DivideByZero { index })
+        }
+    }
+}
+
+impl Observer for () {
+    #[inline]
+    fn write(&mut self, _: usize, _: usize, _: Register) {}
+}
+
+/// An instruction tried to divide by zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DivideByZero {
+    /// The index of the instruction in the program
+    pub index: usize,
+}
+
+impl fmt::Display for DivideByZero {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "division by zero in instruction {}", self.index)
+    }
+}
+
+impl error::Error for DivideByZero {}
+
+/// Assigns each register name an index on first sight
+#[derive(Default)]
+struct Interner<'a> {
+    names: Vec<&'a str>,
+    indices: HashMap<&'a str, usize>,
+}
+
+impl<'a> Interner<'a> {
+    fn intern(&mut self, name: &'a str) -> usize {
+        let names = &mut self.names;
+        *self.indices.entry(name).or_insert_with(|| {
+            names.push(name);
+            names.len() - 1
+        })
+    }
+
+    fn value(&mut self, operand: Operand<'a>) -> Value {
+        match operand {
+            Operand::Register(name) => Value::Slot(self.intern(name)),
+            Operand::Number(value) => Value::Immediate(value),
+        }
+    }
+
+    fn effect(&mut self, action: &Action<'a>) -> Effect {
+        Effect {
+            register: self.intern(action.register),
+            operation: action.operation,
+            value: self.value(action.operand),
+        }
+    }
+
+    fn test(&mut self, condition: &Condition<'a>) -> Test {
+        match *condition {
+            Condition::Compare(left, comparison, right) => {
+                Test::Compare(self.value(left), comparison, self.value(right))
+            }
+            Condition::And(ref left, ref right) => {
+                Test::And(Box::new(self.test(left)), Box::new(self.test(right)))
+            }
+            Condition::Or(ref left, ref right) => {
+                Test::Or(Box::new(self.test(left)), Box::new(self.test(right)))
+            }
+        }
+    }
+}
+
+/// A program ready to execute
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compiled<'a> {
+    names: Vec<&'a str>,
+    code: Vec<Code>,
+}
+
+impl<'a> Compiled<'a> {
+    pub fn new(program: &Program<'a>) -> Compiled<'a> {
+        let mut interner = Interner::default();
+        let code = program
+            .iter()
+            .map(|instruction| Code {
+                effect: interner.effect(instruction.action()),
+                test: interner.test(instruction.condition()),
+                otherwise: instruction
+                    .otherwise()
+                    .map(|action| interner.effect(action)),
+            })
+            .collect();
+
+        Compiled {
+            names: interner.names,
+            code,
+        }
+    }
+
+    /// Every register the program mentions, by index
+    pub fn names(&self) -> &[&'a str] {
+        &self.names
+    }
+
+    pub fn code(&self) -> &[Code] {
+        &self.code
+    }
+
+    pub fn execute(&self) -> Result<Registers<'a>, DivideByZero> {
+        self.execute_observed(&mut ())
+    }
+
+    /// Execute the program, telling the observer about every register write
+    pub fn execute_observed<O: Observer>(
+        &self,
+        observer: &mut O,
+    ) -> Result<Registers<'a>, DivideByZero> {
+        let mut values = vec![0; self.names.len()];
+        for (index, code) in self.code.iter().enumerate() {
+            if let Some(register) = code.process(index, &mut values)? {
+                observer.write(index, register, values[register]);
+            }
+        }
+        Ok(self.registers(values))
+    }
+
+    /// Execute the program, also returning the highest value any register held
+    ///
+    /// Registers start at 0, so this is only `None` for an empty program.
+    pub fn execute_collecting_max(
+        &self,
+    ) -> Result<(Registers<'a>, Option<Peak<'a>>), DivideByZero> {
+        let mut highest = Highest {
+            register: 0,
+            record: Record::INITIAL,
+        };
+        let registers = self.execute_observed(&mut highest)?;
+        let peak = self.names.get(highest.register).map(|&register| Peak {
+            register,
+            record: highest.record,
+        });
+        Ok((registers, peak))
+    }
+
+    fn registers(&self, values: Vec<Register>) -> Registers<'a> {
+        Registers {
+            names: self.names.clone(),
+            values,
+        }
+    }
+}
+
+/// The final value of every register a program mentions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Registers<'a> {
+    names: Vec<&'a str>,
+    values: Vec<Register>,
+}
+
+impl<'a> Registers<'a> {
+    /// The value of the named register, or `None` if the program never mentions it
+    pub fn get(&self, name: &str) -> Option<Register> {
+        self.names
+            .iter()
+            .position(|&n| n == name)
+            .map(|idx| self.values[idx])
+    }
+
+    pub fn values(&self) -> &[Register] {
+        &self.values
+    }
+
+    pub fn iter<'b>(&'b self) -> impl 'b + Iterator<Item = (&'a str, Register)> {
+        self.names.iter().cloned().zip(self.values.iter().cloned())
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::Solution;
+    use {execute, Day08};
+
+    const EXAMPLE: &str = "
+b inc 5 if a > 1
+a inc 1 if b < 5
+c dec -10 if a >= 1
+c inc -20 if c == 10
+";
+
+    #[test]
+    fn test_example() {
+        let program = Day08::parse(EXAMPLE).unwrap();
+        let compiled = Compiled::new(&program);
+        assert_eq!(compiled.names(), &["b", "a", "c"]);
+
+        let (registers, max_value) = compiled.execute_collecting_max().unwrap();
+        assert_eq!(registers.get("a"), Some(1));
+        assert_eq!(registers.get("c"), Some(-10));
+        assert_eq!(registers.get("z"), None);
+        assert_eq!(registers.values().iter().max(), Some(&1));
+        let peak = max_value.unwrap();
+        assert_eq!(peak.register, "c");
+        assert_eq!(peak.record.value, 10);
+        assert_eq!(peak.record.instruction, Some(2));
+        assert_eq!(compiled.execute(), Ok(registers));
+    }
+
+    #[test]
+    fn test_extensions() {
+        let program = Day08::parse(
+            "
+x set 7 if a == 0
+y set x if x > 5 and x < 10
+x mul y if y == 0 or x != y else z dec 2
+x div -2 if z < 0 and x == 7 or y < 0
+set inc 1 if or == 0
+",
+        )
+        .unwrap();
+        let registers = execute(&program).unwrap();
+        assert_eq!(registers.get("x"), Some(-3));
+        assert_eq!(registers.get("y"), Some(7));
+        assert_eq!(registers.get("z"), Some(-2));
+        assert_eq!(registers.get("set"), Some(1));
+    }
+
+    #[test]
+    fn test_divide_by_zero() {
+        let program = Day08::parse("a inc 1 if a == 0\nb div a if a > 5 else a div b").unwrap();
+        assert_eq!(execute(&program), Err(DivideByZero { index: 1 }));
+    }
+}
@@ -1,23 +1,39 @@
+#[allow(warnings, clippy::all)]
 pub mod parser; // generated by lalrpop
 pub use parser::parse_instruction;
 
-use std::collections::HashMap;
+use std::error;
+use std::fmt;
+
+extern crate lalrpop_util;
+use lalrpop_util::ParseError;
+
+extern crate util;
+use util::{Error, Solution};
+
+pub mod interpreter;
+pub use interpreter::{Compiled, Registers};
+pub mod observer;
+pub use observer::{history, History, Observer, Peak, Record};
+pub mod validate;
+pub use validate::{validate, Warning};
+
+pub use interpreter::DivideByZero;
 
 pub type Register = i64;
-// Can't use references as keys, apparently:
-// https://stackoverflow.com/a/32403439/504550
-// Unfortunately, this means that we have to create a String
-// every time we want to check a value. Oh well.
-pub type Registers = HashMap<String, Register>;
 pub type Program<'a> = Vec<Instruction<'a>>;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Operation {
     Inc,
     Dec,
+    Mul,
+    /// Integer division, rounding toward zero
+    Div,
+    Set,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Comparison {
     Gt,
     Lt,
@@ -27,120 +43,257 @@ pub enum Comparison {
     Ne,
 }
 
+impl Comparison {
+    /// apply this comparison to a pair of values
+    pub fn test(self, left: Register, right: Register) -> bool {
+        use Comparison::*;
+        match self {
+            Gt => left > right,
+            Lt => left < right,
+            Gte => left >= right,
+            Lte => left <= right,
+            Eq => left == right,
+            Ne => left != right,
+        }
+    }
+}
+
+/// Either a register's value or a literal number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand<'a> {
+    Register(&'a str),
+    Number(Register),
+}
+
+/// An operation applied to a register
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Action<'a> {
+    pub register: &'a str,
+    pub operation: Operation,
+    pub operand: Operand<'a>,
+}
+
+impl<'a> Action<'a> {
+    pub fn new(register: &'a str, operation: Operation, operand: Operand<'a>) -> Action<'a> {
+        Action {
+            register,
+            operation,
+            operand,
+        }
+    }
+}
+
+/// The test which decides whether an instruction's action happens
+///
+/// `and` binds more tightly than `or`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition<'a> {
+    Compare(Operand<'a>, Comparison, Operand<'a>),
+    And(Box<Condition<'a>>, Box<Condition<'a>>),
+    Or(Box<Condition<'a>>, Box<Condition<'a>>),
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Instruction<'a> {
-    register: &'a str,
-    operation: Operation,
-    qty: Register,
-    compare_register: &'a str,
-    comparison: Comparison,
-    compare_qty: Register,
+    action: Action<'a>,
+    condition: Condition<'a>,
+    otherwise: Option<Action<'a>>,
 }
 
 impl<'a> Instruction<'a> {
     pub fn new(
-        register: &'a str,
-        operation: Operation,
-        qty: Register,
-        compare_register: &'a str,
-        comparison: Comparison,
-        compare_qty: Register,
+        action: Action<'a>,
+        condition: Condition<'a>,
+        otherwise: Option<Action<'a>>,
     ) -> Instruction<'a> {
         Instruction {
-            register: register,
-            operation: operation,
-            qty: qty,
-            compare_register: compare_register,
-            comparison: comparison,
-            compare_qty: compare_qty,
+            action,
+            condition,
+            otherwise,
         }
     }
 
-    /// apply the comparison in this instruction
-    fn compare(&self, registers: &mut Registers) -> bool {
-        use Comparison::*;
-        match self.comparison {
-            Gt => {
-                *registers
-                    .entry(self.compare_register.to_string())
-                    .or_insert(0) > self.compare_qty
-            }
-            Lt => {
-                *registers
-                    .entry(self.compare_register.to_string())
-                    .or_insert(0) < self.compare_qty
-            }
-            Gte => {
-                *registers
-                    .entry(self.compare_register.to_string())
-                    .or_insert(0) >= self.compare_qty
-            }
-            Lte => {
-                *registers
-                    .entry(self.compare_register.to_string())
-                    .or_insert(0) <= self.compare_qty
-            }
-            Eq => {
-                *registers
-                    .entry(self.compare_register.to_string())
-                    .or_insert(0) == self.compare_qty
-            }
-            Ne => {
-                *registers
-                    .entry(self.compare_register.to_string())
-                    .or_insert(0) != self.compare_qty
+    /// The action taken when the condition holds
+    pub fn action(&self) -> &Action<'a> {
+        &self.action
+    }
+
+    pub fn condition(&self) -> &Condition<'a> {
+        &self.condition
+    }
+
+    /// The action taken when the condition fails, if any
+    pub fn otherwise(&self) -> Option<&Action<'a>> {
+        self.otherwise.as_ref()
+    }
+}
+
+/// A number in a line which doesn't fit in a register, by byte offsets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Why a single line of a program couldn't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineError {
+    /// 1-indexed
+    pub line: usize,
+    /// The byte offset within the line at which parsing failed, as reported by the parser
+    pub location: usize,
+    /// The unexpected text, or `None` if the line ended too soon
+    pub found: Option<String>,
+    /// The tokens which could have come next, as the grammar names them
+    pub expected: Vec<String>,
+}
+
+impl LineError {
+    fn new(line: usize, text: &str, err: ParseError<usize, (usize, &str), OutOfRange>) -> LineError {
+        let (location, found, expected) = match err {
+            ParseError::InvalidToken { location } => {
+                let found = text[location..].split_whitespace().next();
+                (location, found, Vec::new())
             }
+            ParseError::UnrecognizedToken {
+                token: Some((start, (_, found), _)),
+                expected,
+            } => (start, Some(found), expected),
+            ParseError::UnrecognizedToken {
+                token: None,
+                expected,
+            } => (text.trim_end().len(), None, expected),
+            ParseError::ExtraToken {
+                token: (start, (_, found), _),
+            } => (start, Some(found), Vec::new()),
+            ParseError::User {
+                error: OutOfRange { start, end },
+            } => (start, Some(&text[start..end]), Vec::new()),
+        };
+        LineError {
+            line,
+            location,
+            found: found.map(str::to_string),
+            expected,
         }
     }
+}
 
-    /// Apply this operation to its register
-    fn apply(&self, registers: &mut Registers) {
-        use Operation::*;
-        match self.operation {
-            Inc => *registers.entry(self.register.to_string()).or_insert(0) += self.qty,
-            Dec => *registers.entry(self.register.to_string()).or_insert(0) -= self.qty,
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.location + 1)?;
+        match self.found {
+            Some(ref found) => write!(f, "unexpected {:?}", found)?,
+            None => write!(f, "unexpected end of line")?,
+        }
+        if !self.expected.is_empty() {
+            write!(f, "; expected one of {}", self.expected.join(", "))?;
         }
+        Ok(())
     }
+}
+
+/// Every line of a program which couldn't be parsed, in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrors(pub Vec<LineError>);
 
-    pub fn process(&self, registers: &mut Registers) {
-        if self.compare(registers) {
-            self.apply(registers);
+impl fmt::Display for ParseErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, err) in self.0.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", err)?;
         }
+        Ok(())
     }
 }
 
-pub fn parse<'a>(input: &'a str) -> Program<'a> {
-    input
-        .lines()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| {
-            parse_instruction(line).expect("All input lines should parse correctly")
-        })
-        .collect()
-}
+impl error::Error for ParseErrors {}
 
-pub fn execute<'a>(program: &Program<'a>) -> Registers {
-    let mut registers = Registers::new();
-    for instruction in program {
-        instruction.process(&mut registers);
+/// Parse a program, collecting an error for every line which fails
+pub fn parse(input: &str) -> Result<Program<'_>, ParseErrors> {
+    let mut program = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_instruction(line) {
+            Ok(instruction) => program.push(instruction),
+            Err(err) => errors.push(LineError::new(idx + 1, line, err)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(program)
+    } else {
+        Err(ParseErrors(errors))
     }
-    registers
+}
+
+pub fn execute<'a>(program: &Program<'a>) -> Result<Registers<'a>, DivideByZero> {
+    Compiled::new(program).execute()
 }
 
 pub fn max_value_in(registers: &Registers) -> Option<Register> {
-    registers.values().max().map(|&r| r)
-}
-
-pub fn execute_collecting_max<'a>(program: &Program<'a>) -> Option<Register> {
-    let mut registers = Registers::new();
-    let mut max_value = None;
-    for instruction in program {
-        instruction.process(&mut registers);
-        max_value = match (max_value, max_value_in(&registers)) {
-            (None, m) => m,
-            (Some(p), Some(c)) if c > p => Some(c),
-            (c, _) => c,
-        };
+    registers.values().iter().max().copied()
+}
+
+pub fn execute_collecting_max<'a>(program: &Program<'a>) -> Result<Option<Register>, DivideByZero> {
+    Ok(highest_value(program)?.map(|peak| peak.record.value))
+}
+
+/// The highest value any register held, with the register and instruction which produced it
+pub fn highest_value<'a>(program: &Program<'a>) -> Result<Option<Peak<'a>>, DivideByZero> {
+    Compiled::new(program)
+        .execute_collecting_max()
+        .map(|(_, peak)| peak)
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input<'a> = Program<'a>;
+    type Answer1 = Register;
+    type Answer2 = Register;
+
+    fn parse(input: &str) -> Result<Program<'_>, Error> {
+        parse(input).map_err(Error::parse)
+    }
+
+    fn part1(program: &Program<'_>) -> Option<Register> {
+        max_value_in(&execute(program).ok()?)
+    }
+
+    fn part2(program: &Program<'_>) -> Option<Register> {
+        execute_collecting_max(program).ok()?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let input = "a inc 1 if b > 2\n\nb inc x2 iff a < 1\nc dec 3 if\nd set 4 if a == 1 $\n\
+                     e inc 99999999999999999999 if b > 1\n";
+        let errors = parse(input).unwrap_err().0;
+        assert_eq!(errors.len(), 4);
+
+        assert_eq!((errors[0].line, errors[0].location), (3, 9));
+        assert_eq!(errors[0].found, Some("iff".to_string()));
+        assert!(errors[0].expected.contains(&r#""if""#.to_string()));
+
+        assert_eq!((errors[1].line, errors[1].location), (4, 10));
+        assert_eq!(errors[1].found, None);
+
+        assert_eq!((errors[2].line, errors[2].location), (5, 18));
+        assert!(errors[2].to_string().starts_with("5:19: unexpected \"$\""));
+
+        assert_eq!((errors[3].line, errors[3].location), (6, 6));
+        assert_eq!(errors[3].found, Some("99999999999999999999".to_string()));
+
+        assert!(parse("a inc 1 if b > 2\n").is_ok());
     }
-    max_value
 }
@@ -0,0 +1,184 @@
+//! Watch a program's registers change as it runs
+//!
+//! An `Observer` is told about every write as it happens, so tracking values
+//! over a whole run costs constant time per instruction.
+
+use {Compiled, DivideByZero, Program, Register};
+
+/// Receives every register write made while executing a program
+pub trait Observer {
+    /// Instruction `instruction` set the register with this index to `value`
+    fn write(&mut self, instruction: usize, register: usize, value: Register);
+}
+
+/// A value a register held, and where it came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Record {
+    pub value: Register,
+    /// The index of the instruction which wrote it, or `None` for the initial 0
+    pub instruction: Option<usize>,
+}
+
+impl Record {
+    pub(crate) const INITIAL: Record = Record {
+        value: 0,
+        instruction: None,
+    };
+}
+
+/// Everything a single register did during a run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterHistory {
+    /// Every value the register held, in order, starting with its initial 0
+    pub values: Vec<Record>,
+    /// The earliest occurrence of the highest value
+    pub max: Record,
+    /// The earliest occurrence of the lowest value
+    pub min: Record,
+}
+
+impl Default for RegisterHistory {
+    fn default() -> RegisterHistory {
+        RegisterHistory {
+            values: vec![Record::INITIAL],
+            max: Record::INITIAL,
+            min: Record::INITIAL,
+        }
+    }
+}
+
+/// A register and a value it held, such as its highest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Peak<'a> {
+    pub register: &'a str,
+    pub record: Record,
+}
+
+/// Records the full history of every register
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History<'a> {
+    names: Vec<&'a str>,
+    registers: Vec<RegisterHistory>,
+}
+
+impl<'a> History<'a> {
+    pub fn new(compiled: &Compiled<'a>) -> History<'a> {
+        History {
+            names: compiled.names().to_vec(),
+            registers: vec![RegisterHistory::default(); compiled.names().len()],
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RegisterHistory> {
+        self.names
+            .iter()
+            .position(|&n| n == name)
+            .map(|idx| &self.registers[idx])
+    }
+
+    pub fn iter<'b>(&'b self) -> impl 'b + Iterator<Item = (&'a str, &'b RegisterHistory)> {
+        self.names.iter().cloned().zip(self.registers.iter())
+    }
+
+    /// The highest value any register held; ties go to the earliest write
+    pub fn max(&self) -> Option<Peak<'a>> {
+        self.iter()
+            .map(|(register, history)| Peak {
+                register,
+                record: history.max,
+            })
+            .max_by_key(|peak| {
+                (
+                    peak.record.value,
+                    std::cmp::Reverse(peak.record.instruction),
+                )
+            })
+    }
+
+    /// The lowest value any register held; ties go to the earliest write
+    pub fn min(&self) -> Option<Peak<'a>> {
+        self.iter()
+            .map(|(register, history)| Peak {
+                register,
+                record: history.min,
+            })
+            .min_by_key(|peak| (peak.record.value, peak.record.instruction))
+    }
+}
+
+impl<'a> Observer for History<'a> {
+    fn write(&mut self, instruction: usize, register: usize, value: Register) {
+        let record = Record {
+            value,
+            instruction: Some(instruction),
+        };
+        let history = &mut self.registers[register];
+        history.values.push(record);
+        if value > history.max.value {
+            history.max = record;
+        }
+        if value < history.min.value {
+            history.min = record;
+        }
+    }
+}
+
+/// Tracks only the highest value any register held
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Highest {
+    pub(crate) register: usize,
+    pub(crate) record: Record,
+}
+
+impl Observer for Highest {
+    fn write(&mut self, instruction: usize, register: usize, value: Register) {
+        if value > self.record.value {
+            self.register = register;
+            self.record = Record {
+                value,
+                instruction: Some(instruction),
+            };
+        }
+    }
+}
+
+/// Execute a program, recording the history of every register
+pub fn history<'a>(program: &Program<'a>) -> Result<History<'a>, DivideByZero> {
+    let compiled = Compiled::new(program);
+    let mut history = History::new(&compiled);
+    compiled.execute_observed(&mut history)?;
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::Solution;
+    use Day08;
+
+    #[test]
+    fn test_history() {
+        let program = Day08::parse(
+            "
+b inc 5 if a > 1
+a inc 1 if b < 5
+c dec -10 if a >= 1
+c inc -20 if c == 10
+c set 3 if c < 0
+",
+        )
+        .unwrap();
+        let history = history(&program).unwrap();
+
+        let c = history.get("c").unwrap();
+        let values = c.values.iter().map(|r| r.value).collect::<Vec<_>>();
+        assert_eq!(values, vec![0, 10, -10, 3]);
+        assert_eq!(c.max.instruction, Some(2));
+        assert_eq!(c.min.instruction, Some(3));
+        assert_eq!(history.get("b").unwrap().values.len(), 1);
+
+        let peak = history.max().unwrap();
+        assert_eq!((peak.register, peak.record.value), ("c", 10));
+        assert_eq!(history.min().unwrap().record.value, -10);
+    }
+}
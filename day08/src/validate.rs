@@ -0,0 +1,452 @@
+//! Find likely mistakes in a program without running it
+//!
+//! Programs are straight-line code, so a single pass in order can track the
+//! range of values each register might hold. A condition which can't hold
+//! for any values in those ranges is reported, as are registers which are
+//! only ever written or only ever read, and names which look like typos.
+
+use std::cmp::{max, min};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use {Action, Comparison, Condition, Operand, Operation, Program, Register};
+
+/// Something suspicious about a program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning<'a> {
+    /// This instruction's condition can never hold, so its action never runs
+    NeverTrue { instruction: usize },
+    /// This instruction's condition always holds, so its `else` never runs
+    ElseNeverRuns { instruction: usize },
+    /// The register is written, first by this instruction, but never compared or read
+    NeverRead {
+        register: &'a str,
+        instruction: usize,
+    },
+    /// The register is read, first by this instruction, but never written, so it's always 0
+    NeverWritten {
+        register: &'a str,
+        instruction: usize,
+    },
+    /// The register is only written or only read, and its name is close to one which is both
+    LikelyTypo {
+        register: &'a str,
+        intended: &'a str,
+        instruction: usize,
+    },
+}
+
+impl<'a> fmt::Display for Warning<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Warning::*;
+        match *self {
+            NeverTrue { instruction } => {
+                write!(f, "instruction {}: condition can never hold", instruction)
+            }
+            ElseNeverRuns { instruction } => write!(
+                f,
+                "instruction {}: condition always holds, so else never runs",
+                instruction
+            ),
+            NeverRead {
+                register,
+                instruction,
+            } => write!(
+                f,
+                "instruction {}: {} is written but never read",
+                instruction, register
+            ),
+            NeverWritten {
+                register,
+                instruction,
+            } => write!(
+                f,
+                "instruction {}: {} is read but never written",
+                instruction, register
+            ),
+            LikelyTypo {
+                register,
+                intended,
+                instruction,
+            } => write!(
+                f,
+                "instruction {}: {} may be a typo for {}",
+                instruction, register, intended
+            ),
+        }
+    }
+}
+
+/// The inclusive range of values a register might hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Range {
+    lo: Register,
+    hi: Register,
+}
+
+impl Range {
+    const ZERO: Range = Range { lo: 0, hi: 0 };
+    const ALL: Range = Range {
+        lo: Register::MIN,
+        hi: Register::MAX,
+    };
+
+    fn exactly(value: Register) -> Range {
+        Range {
+            lo: value,
+            hi: value,
+        }
+    }
+
+    /// Bounds computed exactly; anything which overflowed may have wrapped anywhere
+    fn wide(lo: i128, hi: i128) -> Range {
+        if lo < Register::MIN as i128 || hi > Register::MAX as i128 {
+            Range::ALL
+        } else {
+            Range {
+                lo: lo as Register,
+                hi: hi as Register,
+            }
+        }
+    }
+
+    fn join(self, other: Range) -> Range {
+        Range {
+            lo: min(self.lo, other.lo),
+            hi: max(self.hi, other.hi),
+        }
+    }
+
+    fn contains(self, value: Register) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    /// The range of results of an operation, or `None` if it always divides by zero
+    fn apply(self, operation: Operation, operand: Range) -> Option<Range> {
+        let (lo, hi) = (self.lo as i128, self.hi as i128);
+        let (olo, ohi) = (operand.lo as i128, operand.hi as i128);
+        let extremes = |values: &[i128]| {
+            let lo = values.iter().cloned().min().unwrap();
+            let hi = values.iter().cloned().max().unwrap();
+            Range::wide(lo, hi)
+        };
+        Some(match operation {
+            Operation::Inc => Range::wide(lo + olo, hi + ohi),
+            Operation::Dec => Range::wide(lo - ohi, hi - olo),
+            Operation::Mul => extremes(&[lo * olo, lo * ohi, hi * olo, hi * ohi]),
+            Operation::Div => {
+                // for a fixed dividend, the extremes come from the divisors
+                // furthest from and closest to zero
+                let mut divisors = vec![olo, ohi];
+                divisors.extend([-1, 1].iter().filter(|&&d| operand.contains(d as Register)));
+                divisors.retain(|&d| d != 0);
+                if divisors.is_empty() {
+                    return None;
+                }
+                let quotients = divisors
+                    .iter()
+                    .flat_map(|&d| vec![lo / d, hi / d])
+                    .collect::<Vec<_>>();
+                extremes(&quotients)
+            }
+            Operation::Set => operand,
+        })
+    }
+}
+
+/// Whether a condition might hold, and whether it might fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Outcomes {
+    may_hold: bool,
+    may_fail: bool,
+}
+
+fn compare(left: Range, comparison: Comparison, right: Range) -> Outcomes {
+    use Comparison::*;
+    let (may_hold, may_fail) = match comparison {
+        Gt => (left.hi > right.lo, left.lo <= right.hi),
+        Lt => (left.lo < right.hi, left.hi >= right.lo),
+        Gte => (left.hi >= right.lo, left.lo < right.hi),
+        Lte => (left.lo <= right.hi, left.hi > right.lo),
+        Eq | Ne => {
+            let overlap = left.lo <= right.hi && right.lo <= left.hi;
+            let same = left.lo == left.hi && left == right;
+            if comparison == Eq {
+                (overlap, !same)
+            } else {
+                (!same, overlap)
+            }
+        }
+    };
+    Outcomes { may_hold, may_fail }
+}
+
+struct Analysis<'a> {
+    ranges: HashMap<&'a str, Range>,
+}
+
+impl<'a> Analysis<'a> {
+    fn range(&self, operand: Operand<'a>) -> Range {
+        match operand {
+            Operand::Register(name) => self.ranges.get(name).cloned().unwrap_or(Range::ZERO),
+            Operand::Number(value) => Range::exactly(value),
+        }
+    }
+
+    fn outcomes(&self, condition: &Condition<'a>) -> Outcomes {
+        match *condition {
+            // a register compared with itself has only one possible outcome
+            Condition::Compare(left, comparison, right) if left == right => {
+                let holds = compare(Range::ZERO, comparison, Range::ZERO).may_hold;
+                Outcomes {
+                    may_hold: holds,
+                    may_fail: !holds,
+                }
+            }
+            Condition::Compare(left, comparison, right) => {
+                compare(self.range(left), comparison, self.range(right))
+            }
+            Condition::And(ref left, ref right) => {
+                let (left, right) = (self.outcomes(left), self.outcomes(right));
+                Outcomes {
+                    may_hold: left.may_hold && right.may_hold,
+                    may_fail: left.may_fail || right.may_fail,
+                }
+            }
+            Condition::Or(ref left, ref right) => {
+                let (left, right) = (self.outcomes(left), self.outcomes(right));
+                Outcomes {
+                    may_hold: left.may_hold || right.may_hold,
+                    may_fail: left.may_fail && right.may_fail,
+                }
+            }
+        }
+    }
+
+    /// The register an action writes and the range it might write, if it can succeed
+    fn effect(&self, action: &Action<'a>) -> Option<(&'a str, Range)> {
+        let current = self.range(Operand::Register(action.register));
+        current
+            .apply(action.operation, self.range(action.operand))
+            .map(|range| (action.register, range))
+    }
+}
+
+fn condition_registers<'a>(condition: &Condition<'a>, registers: &mut Vec<&'a str>) {
+    match *condition {
+        Condition::Compare(left, _, right) => {
+            for operand in &[left, right] {
+                if let Operand::Register(name) = *operand {
+                    registers.push(name);
+                }
+            }
+        }
+        Condition::And(ref left, ref right) | Condition::Or(ref left, ref right) => {
+            condition_registers(left, registers);
+            condition_registers(right, registers);
+        }
+    }
+}
+
+/// The number of single-character insertions, deletions, substitutions, or
+/// swaps of adjacent characters between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    // distances[i][j] is the distance between a[..i] and b[..j]
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    distances[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut distance = min(
+                distances[i - 1][j - 1] + cost,
+                min(distances[i - 1][j], distances[i][j - 1]) + 1,
+            );
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = min(distance, distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// Check a program for likely mistakes, in order of the instructions involved
+pub fn validate<'a>(program: &Program<'a>) -> Vec<Warning<'a>> {
+    let mut warnings = Vec::new();
+    let mut analysis = Analysis {
+        ranges: HashMap::new(),
+    };
+    // the first instruction to write, and to read, each register
+    let mut written: BTreeMap<&'a str, usize> = BTreeMap::new();
+    let mut read: BTreeMap<&'a str, usize> = BTreeMap::new();
+
+    for (index, instruction) in program.iter().enumerate() {
+        let mut reads = Vec::new();
+        condition_registers(instruction.condition(), &mut reads);
+        let actions = Some(instruction.action())
+            .into_iter()
+            .chain(instruction.otherwise());
+        for action in actions {
+            written.entry(action.register).or_insert(index);
+            if let Operand::Register(name) = action.operand {
+                reads.push(name);
+            }
+        }
+        for name in reads {
+            read.entry(name).or_insert(index);
+        }
+
+        let outcomes = analysis.outcomes(instruction.condition());
+        if !outcomes.may_hold {
+            warnings.push(Warning::NeverTrue { instruction: index });
+        }
+        if !outcomes.may_fail && instruction.otherwise().is_some() {
+            warnings.push(Warning::ElseNeverRuns { instruction: index });
+        }
+
+        // each branch which might run writes at most one register
+        let mut branches = Vec::new();
+        if outcomes.may_hold {
+            branches.push(analysis.effect(instruction.action()));
+        }
+        if outcomes.may_fail {
+            branches.push(instruction.otherwise().and_then(|a| analysis.effect(a)));
+        }
+        let mut updates = Vec::new();
+        for &(register, _) in branches.iter().flatten() {
+            let before = analysis.range(Operand::Register(register));
+            let after = branches
+                .iter()
+                .map(|branch| match *branch {
+                    Some((written, range)) if written == register => range,
+                    _ => before,
+                })
+                .fold(None, |acc: Option<Range>, range| {
+                    Some(acc.map_or(range, |acc| acc.join(range)))
+                })
+                .unwrap_or(before);
+            updates.push((register, after));
+        }
+        analysis.ranges.extend(updates);
+    }
+
+    let both = |name: &str| written.contains_key(name) && read.contains_key(name);
+    let mut dead = Vec::new();
+    for (&register, &instruction) in &written {
+        if !read.contains_key(register) {
+            dead.push((
+                instruction,
+                register,
+                Warning::NeverRead {
+                    register,
+                    instruction,
+                },
+            ));
+        }
+    }
+    for (&register, &instruction) in &read {
+        if !written.contains_key(register) {
+            dead.push((
+                instruction,
+                register,
+                Warning::NeverWritten {
+                    register,
+                    instruction,
+                },
+            ));
+        }
+    }
+    for (instruction, register, warning) in dead {
+        let threshold = max(1, register.len() / 4);
+        let intended = written
+            .keys()
+            .filter(|&&name| both(name))
+            .map(|&name| (edit_distance(register, name), name))
+            .filter(|&(distance, _)| distance <= threshold)
+            .min();
+        warnings.push(match intended {
+            Some((_, intended)) => Warning::LikelyTypo {
+                register,
+                intended,
+                instruction,
+            },
+            None => warning,
+        });
+    }
+
+    warnings.sort_by_key(|warning| match *warning {
+        Warning::NeverTrue { instruction }
+        | Warning::ElseNeverRuns { instruction }
+        | Warning::NeverRead { instruction, .. }
+        | Warning::NeverWritten { instruction, .. }
+        | Warning::LikelyTypo { instruction, .. } => instruction,
+    });
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::Solution;
+    use Day08;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("abc", "abc"), 0);
+        assert_eq!(edit_distance("abc", "abd"), 1);
+        assert_eq!(edit_distance("count", "cuont"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "ab"), 2);
+    }
+
+    #[test]
+    fn test_ranges() {
+        let a = Range { lo: -3, hi: 5 };
+        let b = Range { lo: -1, hi: 2 };
+        assert_eq!(a.apply(Operation::Mul, b), Some(Range { lo: -6, hi: 10 }));
+        assert_eq!(a.apply(Operation::Div, b), Some(Range { lo: -5, hi: 5 }));
+        assert_eq!(a.apply(Operation::Div, Range::ZERO), None);
+        assert_eq!(a.apply(Operation::Dec, b), Some(Range { lo: -5, hi: 6 }));
+        let big = Range::exactly(Register::MAX);
+        assert_eq!(big.apply(Operation::Inc, b), Some(Range::ALL));
+    }
+
+    #[test]
+    fn test_validate() {
+        let program = Day08::parse(
+            "
+count inc 1 if flag == 0
+count inc 1 if count > 5
+limit set 3 if count >= 1 else flag set 1
+total inc count if count > 0 and count < 3
+count dec 1 if cuont == 0
+",
+        )
+        .unwrap();
+        assert_eq!(
+            validate(&program),
+            vec![
+                Warning::NeverTrue { instruction: 1 },
+                Warning::ElseNeverRuns { instruction: 2 },
+                Warning::NeverRead {
+                    register: "limit",
+                    instruction: 2,
+                },
+                Warning::NeverRead {
+                    register: "total",
+                    instruction: 3,
+                },
+                Warning::LikelyTypo {
+                    register: "cuont",
+                    intended: "count",
+                    instruction: 4,
+                },
+            ]
+        );
+    }
+}
@@ -1,14 +1,39 @@
 pub mod parser;
 pub use parser::parse;
+pub mod stream;
+pub use stream::{scan, Scanner};
+pub mod recover;
+pub use recover::{parse_recovering, Diagnostic, Recovered};
+
+extern crate util;
+use util::{Error, Solution};
+
+/// A range of byte offsets into the parsed text, excluding `end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    /// The text this span covers
+    pub fn of<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.start..self.end]
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Thing {
     Group(Box<Group>),
-    Garbage(usize),
+    Garbage(Garbage),
 }
 
 impl Thing {
-    pub fn ok<'a>(&'a self) -> Option<&'a Box<Group>> {
+    pub fn ok(&self) -> Option<&Group> {
         use Thing::*;
         match *self {
             Group(ref box_group) => Some(box_group),
@@ -19,23 +44,80 @@ impl Thing {
     pub fn garbage_chars(&self) -> usize {
         use Thing::*;
         match *self {
-            Garbage(g) => g,
+            Garbage(ref garbage) => garbage.garbage_chars(),
             Group(ref group) => group.garbage_chars(),
         }
     }
+
+    pub fn span(&self) -> Span {
+        use Thing::*;
+        match *self {
+            Garbage(ref garbage) => garbage.span,
+            Group(ref group) => group.span,
+        }
+    }
+}
+
+/// A garbage section, `<` to `>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Garbage {
+    /// Includes the delimiters
+    pub span: Span,
+    /// Everything between the delimiters, escapes included
+    pub raw: String,
+    /// The offset of each `!` which cancels the following character
+    pub escapes: Vec<usize>,
+}
+
+impl Garbage {
+    pub fn new(span: Span, raw: &str, escapes: Vec<usize>) -> Garbage {
+        Garbage {
+            span,
+            raw: raw.to_string(),
+            escapes,
+        }
+    }
+
+    /// The characters which aren't escapes or cancelled by them
+    fn chars<'a>(&'a self) -> impl 'a + Iterator<Item = char> {
+        let mut cancelled = false;
+        self.raw.chars().filter(move |&ch| {
+            if cancelled {
+                cancelled = false;
+                false
+            } else {
+                cancelled = ch == '!';
+                !cancelled
+            }
+        })
+    }
+
+    /// The characters which aren't escapes or cancelled by them
+    pub fn text(&self) -> String {
+        self.chars().collect()
+    }
+
+    /// The number of characters which aren't escapes or cancelled by them
+    pub fn garbage_chars(&self) -> usize {
+        self.chars().count()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Group {
+    /// Includes the braces
+    pub span: Span,
     things: Vec<Thing>,
 }
 
 impl Group {
-    pub fn new<S: AsRef<[Thing]>>(things: &S) -> Group {
-        let things = things.as_ref();
-        let mut things_vec = Vec::with_capacity(things.len());
-        things_vec.extend(things.iter().cloned());
-        Group { things: things_vec }
+    pub fn new(span: Span, things: Vec<Thing>) -> Group {
+        Group { span, things }
+    }
+
+    /// The groups and garbage directly inside this group, in order
+    pub fn things(&self) -> &[Thing] {
+        &self.things
     }
 
     pub fn score(&self) -> usize {
@@ -66,6 +148,36 @@ impl Group {
     }
 }
 
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input<'a> = Group;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Group, Error> {
+        let input = input.trim_end();
+        let start = input.len() - input.trim_start().len();
+        match parser::parse_at(input, start) {
+            Ok((Thing::Group(group), "")) => Ok(*group),
+            Ok((Thing::Group(_), remaining)) => Err(Error::parse(format!(
+                "unexpected input after outer group: {:?}",
+                remaining
+            ))),
+            Ok((Thing::Garbage(_), _)) => Err(Error::parse("Outer parse was garbage not group")),
+            Err(e) => Err(Error::parse(format!("{:?}", e))),
+        }
+    }
+
+    fn part1(group: &Group) -> Option<usize> {
+        Some(group.score())
+    }
+
+    fn part2(group: &Group) -> Option<usize> {
+        Some(group.garbage_chars())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,7 +197,7 @@ mod tests {
 
     #[test]
     fn test_count() {
-        let expected = vec![1, 3, 3, 6, 1, 1, 5, 2];
+        let expected = [1, 3, 3, 6, 1, 1, 5, 2];
         for (g, e) in example_groups().iter().zip(expected.iter()) {
             println!("Expecting {e} groups in \"{g}\"...", g = g, e = e);
             let parsed = parse(g);
@@ -99,14 +211,14 @@ mod tests {
                         None => panic!("Parsed a group as garbage!"),
                     }
                 }
-                e => panic!(e),
+                e => panic!("{:?}", e),
             }
         }
     }
 
     #[test]
     fn test_score() {
-        let expected = vec![1, 6, 5, 16, 1, 1, 9, 3];
+        let expected = [1, 6, 5, 16, 1, 1, 9, 3];
         for (g, e) in example_groups().iter().zip(expected.iter()) {
             println!("Expecting \"{g}\" to score {e}...", g = g, e = e);
             let parsed = parse(g);
@@ -120,8 +232,39 @@ mod tests {
                         None => panic!("Parsed a group as garbage!"),
                     }
                 }
-                e => panic!(e),
+                e => panic!("{:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_spans() {
+        let stream = "{{<a!>b>},<é!!>,{}}";
+        let group = Day09::parse(stream).unwrap();
+        assert_eq!(group.span, Span::new(0, stream.len()));
+
+        let things = group.things();
+        assert_eq!(things.len(), 3);
+        assert_eq!(things[0].span().of(stream), "{<a!>b>}");
+        assert_eq!(things[2].span().of(stream), "{}");
+
+        let inner = things[0].ok().unwrap().things();
+        match inner[0] {
+            Thing::Garbage(ref garbage) => {
+                assert_eq!(garbage.span, Span::new(2, 8));
+                assert_eq!(garbage.raw, "a!>b");
+                assert_eq!(garbage.escapes, vec![4]);
+                assert_eq!(garbage.text(), "ab");
+            }
+            ref other => panic!("expected garbage, got {:?}", other),
+        }
+        match things[1] {
+            Thing::Garbage(ref garbage) => {
+                assert_eq!(garbage.span.of(stream), "<é!!>");
+                assert_eq!(garbage.escapes, vec![13]);
+                assert_eq!(garbage.garbage_chars(), 1);
             }
+            ref other => panic!("expected garbage, got {:?}", other),
         }
     }
 }
@@ -1,4 +1,4 @@
-use super::{Group, Thing};
+use super::{Garbage, Group, Span, Thing};
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ParseError<'a> {
@@ -8,60 +8,81 @@ pub enum ParseError<'a> {
 
 pub type ParseResult<'a> = Result<(Thing, &'a str), ParseError<'a>>;
 
+/// The parsed thing, and the byte offset just past its end
+type Parsed<'a> = Result<(Thing, usize), ParseError<'a>>;
+
 pub fn parse<'a>(input: &'a str) -> ParseResult<'a> {
-    parse_group(input)
+    parse_at(input, 0)
+}
+
+/// Parse a group starting at byte offset `start`
+///
+/// Spans in the result are byte offsets into `source`.
+pub fn parse_at<'a>(source: &'a str, start: usize) -> ParseResult<'a> {
+    parse_group(source, start).map(|(thing, end)| (thing, &source[end..]))
 }
 
-fn parse_group<'a>(mut input: &'a str) -> ParseResult<'a> {
+fn parse_group<'a>(source: &'a str, start: usize) -> Parsed<'a> {
     // confirm that we're starting a group
-    if input.chars().next() != Some('{') {
-        return Err(ParseError::ParseGroupError(input));
+    if !source[start..].starts_with('{') {
+        return Err(ParseError::ParseGroupError(&source[start..]));
     }
-    input = &input[1..];
+    let mut position = start + 1;
 
     let mut things = Vec::new();
 
     loop {
-        input = match input.chars().next() {
-            Some(',') => &input[1..],
+        position = match source[position..].chars().next() {
+            Some(',') => position + 1,
             Some('{') => {
-                let (group, input) = parse_group(input)?;
+                let (group, position) = parse_group(source, position)?;
                 things.push(group);
-                input
+                position
             }
             Some('<') => {
-                let (garbage, input) = parse_garbage(input)?;
+                let (garbage, position) = parse_garbage(source, position)?;
                 things.push(garbage);
-                input
+                position
             }
-            Some('}') => return Ok((Thing::Group(Box::new(Group::new(&things))), &input[1..])),
-            _ => return Err(ParseError::ParseGroupError(input)),
+            Some('}') => {
+                let span = Span::new(start, position + 1);
+                return Ok((Thing::Group(Box::new(Group::new(span, things))), span.end));
+            }
+            _ => return Err(ParseError::ParseGroupError(&source[position..])),
         }
     }
 }
 
-fn parse_garbage<'a>(mut input: &'a str) -> ParseResult<'a> {
+fn parse_garbage<'a>(source: &'a str, start: usize) -> Parsed<'a> {
     // confirm that we're starting a garbage section
-    if input.chars().next() != Some('<') {
-        return Err(ParseError::ParseGarbageError(input));
+    if !source[start..].starts_with('<') {
+        return Err(ParseError::ParseGarbageError(&source[start..]));
     }
-    input = &input[1..];
+    let contents = start + 1;
 
-    let mut garbage_chars = 0;
+    let mut escapes = Vec::new();
     let mut escaped = false;
-    for (idx, ch) in input.chars().enumerate() {
+    for (idx, ch) in source[contents..].char_indices() {
         if escaped {
             escaped = false;
             continue;
         }
         match ch {
-            '!' => escaped = true,
-            '>' => return Ok((Thing::Garbage(garbage_chars), &input[(idx + 1)..])),
-            _ => garbage_chars += 1,
+            '!' => {
+                escaped = true;
+                escapes.push(contents + idx);
+            }
+            '>' => {
+                let end = contents + idx + 1;
+                let garbage =
+                    Garbage::new(Span::new(start, end), &source[contents..end - 1], escapes);
+                return Ok((Thing::Garbage(garbage), end));
+            }
+            _ => {}
         }
     }
 
-    Err(ParseError::ParseGarbageError(&input[input.len()..]))
+    Err(ParseError::ParseGarbageError(&source[source.len()..]))
 }
 
 #[cfg(test)]
@@ -80,8 +101,8 @@ mod tests {
             "<{o\"i!a,<{i<a>",
         ];
         for g in garbage {
-            match parse_garbage(g) {
-                Ok((Thing::Garbage(_), "")) => {}
+            match parse_garbage(g, 0) {
+                Ok((Thing::Garbage(_), end)) if end == g.len() => {}
                 _ => {
                     panic!("Failed to completely parse '{}' as garbage", g);
                 }
@@ -102,12 +123,12 @@ mod tests {
             "{{<!>},{<!>},{<!>},{<a>}}",
         ];
         for g in groups {
-            match parse_group(g) {
-                Ok((g, remaining)) => {
-                    assert!(g.ok().is_some());
-                    assert_eq!(remaining, "");
+            match parse_group(g, 0) {
+                Ok((group, end)) => {
+                    assert!(group.ok().is_some());
+                    assert_eq!(end, g.len());
                 }
-                e => panic!(e),
+                e => panic!("{:?}", e),
             }
         }
     }
@@ -125,7 +146,7 @@ mod tests {
         ];
         for (g, e) in cases {
             assert_eq!(
-                parse_garbage(g)
+                parse_garbage(g, 0)
                     .expect("failed to parse garbage")
                     .0
                     .garbage_chars(),
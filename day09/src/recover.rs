@@ -0,0 +1,314 @@
+//! Parse damaged streams as well as possible
+//!
+//! Where `parse` stops at the first problem, `parse_recovering` notes it and
+//! carries on, so a corrupted capture still yields most of its tree. It keeps
+//! open groups on an explicit stack rather than recursing.
+
+use std::fmt;
+
+use {Garbage, Group, Span, Thing};
+
+/// A problem found, and worked around, while parsing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// Characters which can't appear here were ignored
+    Skipped { span: Span },
+    /// A `}` before any group was opened was ignored
+    UnmatchedClose { offset: usize },
+    /// The group opened at `offset` was still open at the end of the input
+    UnclosedGroup { offset: usize },
+    /// The garbage opened at `offset` had no `>`
+    ///
+    /// It's taken to end just before the first `}` inside it, or at the end of
+    /// the input if there is none, and parsing resumes from there.
+    UnterminatedGarbage { offset: usize, resumed: usize },
+    /// Everything after the outer group was ignored
+    TrailingInput { span: Span },
+    /// The input contained no group at all
+    MissingGroup,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::Diagnostic::*;
+        match *self {
+            Skipped { span } => write!(f, "{}..{}: skipped unexpected input", span.start, span.end),
+            UnmatchedClose { offset } => write!(f, "{}: unmatched '}}'", offset),
+            UnclosedGroup { offset } => write!(f, "{}: group never closed", offset),
+            UnterminatedGarbage { offset, resumed } => write!(
+                f,
+                "{}: garbage never closed; resumed at {}",
+                offset, resumed
+            ),
+            TrailingInput { span } => write!(
+                f,
+                "{}..{}: ignored input after the outer group",
+                span.start, span.end
+            ),
+            MissingGroup => write!(f, "no group found"),
+        }
+    }
+}
+
+/// The best tree which could be made of some input, and what went wrong
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recovered {
+    /// The outer group, unless there wasn't one
+    pub group: Option<Group>,
+    /// In the order they were found
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Recovered {
+    /// Whether the input parsed without any problems
+    pub fn is_clean(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+/// A group which hasn't been closed yet
+struct Frame {
+    start: usize,
+    things: Vec<Thing>,
+}
+
+/// Parse garbage starting at `start`, recovering if it's never closed
+///
+/// `exhausted` is set once a scan reaches the end of the input without finding
+/// a `>`. Every later garbage starts past that scan's `<`, and sees the same
+/// escapes from its first character on, so it can't find a `>` either: it only
+/// looks for the `}` where it ends, keeping recovery linear.
+fn garbage(
+    source: &str,
+    start: usize,
+    exhausted: &mut bool,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> (Garbage, usize) {
+    let contents = start + 1;
+    let mut escapes = Vec::new();
+    let mut escaped = false;
+    let mut first_close = None;
+    for (idx, ch) in source[contents..].char_indices() {
+        let offset = contents + idx;
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '!' => {
+                escaped = true;
+                escapes.push(offset);
+            }
+            '>' => {
+                let garbage = Garbage::new(
+                    Span::new(start, offset + 1),
+                    &source[contents..offset],
+                    escapes,
+                );
+                return (garbage, offset + 1);
+            }
+            '}' if first_close.is_none() => {
+                first_close = Some(offset);
+                if *exhausted {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    *exhausted = true;
+    let end = first_close.unwrap_or(source.len());
+    escapes.retain(|&offset| offset < end);
+    diagnostics.push(Diagnostic::UnterminatedGarbage {
+        offset: start,
+        resumed: end,
+    });
+    let garbage = Garbage::new(Span::new(start, end), &source[contents..end], escapes);
+    (garbage, end)
+}
+
+/// Ignore the input in `span`, merging it with directly preceding ignored input
+fn skip(diagnostics: &mut Vec<Diagnostic>, span: Span) {
+    if let Some(&mut Diagnostic::Skipped { span: ref mut last }) = diagnostics.last_mut() {
+        if last.end == span.start {
+            last.end = span.end;
+            return;
+        }
+    }
+    diagnostics.push(Diagnostic::Skipped { span });
+}
+
+/// Parse as much of a stream as possible, noting each problem along the way
+///
+/// Spans and offsets are bytes into `source`. Input which `parse` accepts
+/// produces the same tree here, with no diagnostics.
+pub fn parse_recovering(source: &str) -> Recovered {
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut root = None;
+    let mut diagnostics = Vec::new();
+    let mut exhausted = false;
+
+    let mut position = 0;
+    while let Some(ch) = source[position..].chars().next() {
+        let next = position + ch.len_utf8();
+        if root.is_some() {
+            if !ch.is_whitespace() {
+                let end = source.trim_end().len();
+                diagnostics.push(Diagnostic::TrailingInput {
+                    span: Span::new(position, end),
+                });
+                break;
+            }
+            position = next;
+            continue;
+        }
+
+        match ch {
+            '{' => stack.push(Frame {
+                start: position,
+                things: Vec::new(),
+            }),
+            '}' => match stack.pop() {
+                Some(frame) => {
+                    let group = Group::new(Span::new(frame.start, next), frame.things);
+                    match stack.last_mut() {
+                        Some(parent) => parent.things.push(Thing::Group(Box::new(group))),
+                        None => root = Some(group),
+                    }
+                }
+                None => diagnostics.push(Diagnostic::UnmatchedClose { offset: position }),
+            },
+            '<' if !stack.is_empty() => {
+                let (garbage, end) = garbage(source, position, &mut exhausted, &mut diagnostics);
+                if let Some(frame) = stack.last_mut() {
+                    frame.things.push(Thing::Garbage(garbage));
+                }
+                position = end;
+                continue;
+            }
+            ',' if !stack.is_empty() => {}
+            ch if stack.is_empty() && ch.is_whitespace() => {}
+            _ => skip(&mut diagnostics, Span::new(position, next)),
+        }
+        position = next;
+    }
+
+    // close whatever is still open at the end of the input
+    for frame in &stack {
+        diagnostics.push(Diagnostic::UnclosedGroup {
+            offset: frame.start,
+        });
+    }
+    while let Some(frame) = stack.pop() {
+        let group = Group::new(Span::new(frame.start, source.len()), frame.things);
+        match stack.last_mut() {
+            Some(parent) => parent.things.push(Thing::Group(Box::new(group))),
+            None => root = Some(group),
+        }
+    }
+
+    if root.is_none() {
+        diagnostics.push(Diagnostic::MissingGroup);
+    }
+    Recovered {
+        group: root,
+        diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::Solution;
+    use Day09;
+
+    #[test]
+    fn test_clean_input() {
+        let stream = "{{<!>},{<a!>b>},{{}},<é>}";
+        let recovered = parse_recovering(stream);
+        assert!(recovered.is_clean());
+        assert_eq!(recovered.group, Some(Day09::parse(stream).unwrap()));
+    }
+
+    #[test]
+    fn test_unterminated_garbage() {
+        let stream = "{{<ab},{}}";
+        let recovered = parse_recovering(stream);
+        assert_eq!(
+            recovered.diagnostics,
+            vec![Diagnostic::UnterminatedGarbage {
+                offset: 2,
+                resumed: 5,
+            }]
+        );
+        let group = recovered.group.unwrap();
+        assert_eq!(group.span, Span::new(0, 10));
+        assert_eq!(group.count(), 3);
+        assert_eq!(group.garbage_chars(), 2);
+    }
+
+    #[test]
+    fn test_unbalanced_braces() {
+        let recovered = parse_recovering("}{a{,b}x}!");
+        assert_eq!(
+            recovered.diagnostics,
+            vec![
+                Diagnostic::UnmatchedClose { offset: 0 },
+                Diagnostic::Skipped {
+                    span: Span::new(2, 3),
+                },
+                Diagnostic::Skipped {
+                    span: Span::new(5, 6),
+                },
+                Diagnostic::Skipped {
+                    span: Span::new(7, 8),
+                },
+                Diagnostic::TrailingInput {
+                    span: Span::new(9, 10),
+                },
+            ]
+        );
+        assert_eq!(recovered.group.unwrap().span, Span::new(1, 9));
+
+        let recovered = parse_recovering("{{}<a!");
+        assert_eq!(
+            recovered.diagnostics,
+            vec![
+                Diagnostic::UnterminatedGarbage {
+                    offset: 3,
+                    resumed: 6,
+                },
+                Diagnostic::UnclosedGroup { offset: 0 },
+            ]
+        );
+        let group = recovered.group.unwrap();
+        assert_eq!(group.count(), 2);
+        // the final `!` has nothing to cancel
+        assert_eq!(group.garbage_chars(), 1);
+    }
+
+    #[test]
+    fn test_many_unterminated_garbage() {
+        let count = 100_000;
+        let stream = format!("{{{}", "{<}".repeat(count));
+        let recovered = parse_recovering(&stream);
+        // each garbage closes its own group, leaving only the outer one open
+        assert_eq!(recovered.diagnostics.len(), count + 1);
+        assert_eq!(
+            recovered.diagnostics[count - 1],
+            Diagnostic::UnterminatedGarbage {
+                offset: 3 * count - 1,
+                resumed: 3 * count,
+            }
+        );
+        assert_eq!(recovered.group.unwrap().count(), count + 1);
+    }
+
+    #[test]
+    fn test_missing_group() {
+        let recovered = parse_recovering("  \n");
+        assert_eq!(recovered.group, None);
+        assert_eq!(recovered.diagnostics, vec![Diagnostic::MissingGroup]);
+    }
+}
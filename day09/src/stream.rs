@@ -0,0 +1,333 @@
+//! Scan a stream in chunks, without building a tree
+//!
+//! `Scanner` is a state machine fed bytes as they arrive. It keeps only the
+//! current nesting depth, so neither the size of the stream nor how deeply
+//! its groups nest affects memory or stack use.
+
+use std::error;
+use std::fmt;
+use std::io::{self, Read};
+
+/// Something which happened in the stream, at a byte offset from its start
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// `depth` is 1 for the outermost group, and is also the group's score
+    GroupOpen {
+        offset: u64,
+        depth: usize,
+    },
+    GroupClose {
+        offset: u64,
+        depth: usize,
+    },
+    GarbageStart {
+        offset: u64,
+    },
+    GarbageEnd {
+        offset: u64,
+    },
+    /// The character at this offset was cancelled by the `!` before it
+    Cancelled {
+        offset: u64,
+    },
+}
+
+/// Running totals over everything scanned so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub groups: usize,
+    pub score: usize,
+    /// Characters within garbage which aren't escapes or cancelled by them
+    pub garbage_chars: usize,
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    /// A byte which can't appear at this point in the stream
+    Unexpected {
+        offset: u64,
+        byte: u8,
+    },
+    /// The stream ended before the outer group was closed
+    UnexpectedEnd {
+        offset: u64,
+    },
+}
+
+impl From<io::Error> for StreamError {
+    fn from(err: io::Error) -> StreamError {
+        StreamError::Io(err)
+    }
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StreamError::Io(ref err) => write!(f, "i/o error: {}", err),
+            StreamError::Unexpected { offset, byte } => {
+                write!(f, "offset {}: unexpected {:?}", offset, char::from(byte))
+            }
+            StreamError::UnexpectedEnd { offset } => {
+                write!(f, "offset {}: unexpected end of stream", offset)
+            }
+        }
+    }
+}
+
+impl error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            StreamError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Before the outer group
+    Start,
+    /// Directly inside a group
+    Group,
+    Garbage,
+    /// Just after a `!` in garbage
+    Cancel,
+    /// After the outer group
+    Done,
+}
+
+/// Whether a byte begins a UTF-8 character, rather than continuing one
+fn starts_char(byte: u8) -> bool {
+    byte & 0b1100_0000 != 0b1000_0000
+}
+
+/// A push-based parser for a stream of groups and garbage
+///
+/// Like `Day09::parse`, this accepts a single outer group, optionally
+/// surrounded by whitespace.
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    state: State,
+    depth: usize,
+    offset: u64,
+    totals: Totals,
+}
+
+impl Default for Scanner {
+    fn default() -> Scanner {
+        Scanner::new()
+    }
+}
+
+impl Scanner {
+    pub fn new() -> Scanner {
+        Scanner {
+            state: State::Start,
+            depth: 0,
+            offset: 0,
+            totals: Totals::default(),
+        }
+    }
+
+    pub fn totals(&self) -> Totals {
+        self.totals
+    }
+
+    /// The number of bytes scanned so far
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Scan the next chunk of the stream, reporting each event with the totals after it
+    ///
+    /// After an error, the scanner should be discarded.
+    pub fn feed<F>(&mut self, chunk: &[u8], mut on_event: F) -> Result<(), StreamError>
+    where
+        F: FnMut(Event, &Totals),
+    {
+        for &byte in chunk {
+            let offset = self.offset;
+            let event = match (self.state, byte) {
+                (State::Start, b'{') | (State::Group, b'{') => {
+                    self.state = State::Group;
+                    self.depth += 1;
+                    self.totals.groups += 1;
+                    self.totals.score += self.depth;
+                    Some(Event::GroupOpen {
+                        offset,
+                        depth: self.depth,
+                    })
+                }
+                (State::Group, b'}') => {
+                    let depth = self.depth;
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        self.state = State::Done;
+                    }
+                    Some(Event::GroupClose { offset, depth })
+                }
+                (State::Group, b',') => None,
+                (State::Group, b'<') => {
+                    self.state = State::Garbage;
+                    Some(Event::GarbageStart { offset })
+                }
+                (State::Garbage, b'>') => {
+                    self.state = State::Group;
+                    Some(Event::GarbageEnd { offset })
+                }
+                (State::Garbage, b'!') => {
+                    self.state = State::Cancel;
+                    None
+                }
+                (State::Garbage, byte) => {
+                    if starts_char(byte) {
+                        self.totals.garbage_chars += 1;
+                    }
+                    None
+                }
+                // the rest of a cancelled multi-byte character is skipped as
+                // garbage, where continuation bytes aren't counted
+                (State::Cancel, _) => {
+                    self.state = State::Garbage;
+                    Some(Event::Cancelled { offset })
+                }
+                (State::Start, byte) | (State::Done, byte) if byte.is_ascii_whitespace() => None,
+                (_, byte) => return Err(StreamError::Unexpected { offset, byte }),
+            };
+            self.offset += 1;
+            if let Some(event) = event {
+                on_event(event, &self.totals);
+            }
+        }
+        Ok(())
+    }
+
+    /// Finish scanning, checking that the outer group was closed
+    pub fn finish(self) -> Result<Totals, StreamError> {
+        match self.state {
+            State::Done => Ok(self.totals),
+            _ => Err(StreamError::UnexpectedEnd {
+                offset: self.offset,
+            }),
+        }
+    }
+}
+
+/// Scan everything from a reader, in chunks
+pub fn scan<R, F>(mut reader: R, mut on_event: F) -> Result<Totals, StreamError>
+where
+    R: Read,
+    F: FnMut(Event, &Totals),
+{
+    let mut scanner = Scanner::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+        scanner.feed(&buffer[..read], &mut on_event)?;
+    }
+    scanner.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::Solution;
+    use Day09;
+
+    #[test]
+    fn test_matches_tree() {
+        let streams = [
+            "{{<!>},{<!>},{<!>},{<a>}}",
+            "{{{},{},{{}}}}",
+            "{<{o\"i!a,<{i<a>,<é!é>}",
+            "  {{<ab>},{<ab>}}\n",
+        ];
+        for stream in &streams {
+            let group = Day09::parse(stream).unwrap();
+            let totals = scan(stream.as_bytes(), |_, _| {}).unwrap();
+            assert_eq!(totals.groups, group.count());
+            assert_eq!(totals.score, group.score());
+            assert_eq!(totals.garbage_chars, group.garbage_chars());
+        }
+    }
+
+    #[test]
+    fn test_events_across_chunks() {
+        let stream = b"{<a!>>,{}}";
+        let mut scanner = Scanner::new();
+        let mut events = Vec::new();
+        for chunk in stream.chunks(3) {
+            scanner
+                .feed(chunk, |event, totals| events.push((event, totals.score)))
+                .unwrap();
+        }
+        assert_eq!(scanner.finish().unwrap().garbage_chars, 1);
+        assert_eq!(
+            events,
+            vec![
+                (
+                    Event::GroupOpen {
+                        offset: 0,
+                        depth: 1
+                    },
+                    1
+                ),
+                (Event::GarbageStart { offset: 1 }, 1),
+                (Event::Cancelled { offset: 4 }, 1),
+                (Event::GarbageEnd { offset: 5 }, 1),
+                (
+                    Event::GroupOpen {
+                        offset: 7,
+                        depth: 2
+                    },
+                    3
+                ),
+                (
+                    Event::GroupClose {
+                        offset: 8,
+                        depth: 2
+                    },
+                    3
+                ),
+                (
+                    Event::GroupClose {
+                        offset: 9,
+                        depth: 1
+                    },
+                    3
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deep_nesting() {
+        let depth = 1_000_000;
+        let mut stream = vec![b'{'; depth];
+        stream.resize(2 * depth, b'}');
+        let totals = scan(&stream[..], |_, _| {}).unwrap();
+        assert_eq!(totals.groups, depth);
+        assert_eq!(totals.score, depth * (depth + 1) / 2);
+    }
+
+    #[test]
+    fn test_errors() {
+        match scan(&b"{{}"[..], |_, _| {}) {
+            Err(StreamError::UnexpectedEnd { offset: 3 }) => {}
+            other => panic!("{:?}", other),
+        }
+        match scan(&b"{}}"[..], |_, _| {}) {
+            Err(StreamError::Unexpected {
+                offset: 2,
+                byte: b'}',
+            }) => {}
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -1,3 +1,6 @@
+extern crate util;
+use util::{Error, Solution};
+
 pub const LOOP_SIZE: usize = 256;
 type Element = u8;
 
@@ -7,17 +10,23 @@ pub struct LoopHash {
     skip: usize,
 }
 
+impl Default for LoopHash {
+    fn default() -> LoopHash {
+        LoopHash::new()
+    }
+}
+
 impl LoopHash {
     pub fn of_length(size: usize) -> LoopHash {
         assert!(size > 0);
-        assert!(size - 1 <= Element::max_value() as usize);
+        assert!(size - 1 <= Element::MAX as usize);
         let mut elements = Vec::with_capacity(size);
         // ensure that this works if `size - 1 == Element::max_size()`
         elements.extend((0 as Element)..((size - 1) as Element));
         elements.push((size - 1) as Element);
         assert_eq!(elements.len(), size);
         LoopHash {
-            elements: elements,
+            elements,
             position: 0,
             skip: 0,
         }
@@ -79,6 +88,34 @@ where
         .collect()
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    /// Part 1 reads the input as a list of lengths; part 2 reads it as raw bytes.
+    type Input<'a> = (Vec<usize>, &'a str);
+    type Answer1 = usize;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<(Vec<usize>, &str), Error> {
+        let input = input.trim();
+        let lengths = input
+            .split(',')
+            .filter(|token| !token.is_empty())
+            .map(|token| token.trim().parse().map_err(Error::parse))
+            .collect::<Result<Vec<usize>, _>>()?;
+        Ok((lengths, input))
+    }
+
+    fn part1((lengths, _): &(Vec<usize>, &str)) -> Option<usize> {
+        let mut lh = LoopHash::new();
+        lh.twist_list(lengths);
+        Some(lh.initial_product())
+    }
+
+    fn part2(&(_, raw): &(Vec<usize>, &str)) -> Option<String> {
+        Some(hash(&raw.as_bytes()))
+    }
+}
 
 #[cfg(test)]
 mod tests {
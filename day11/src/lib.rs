@@ -1,8 +1,10 @@
-use std::ascii::AsciiExt;
 use std::iter::Sum;
 use std::ops::Add;
 use std::str::FromStr;
 
+extern crate util;
+use util::{Error, Solution};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HexDirection {
     N,
@@ -47,6 +49,12 @@ pub struct HexPosition {
     southwest: isize,
 }
 
+impl Default for HexPosition {
+    fn default() -> HexPosition {
+        HexPosition::new()
+    }
+}
+
 impl HexPosition {
     pub fn new() -> HexPosition {
         HexPosition {
@@ -180,3 +188,34 @@ impl<'a> Sum<&'a HexDirection> for HexPosition {
         position
     }
 }
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input<'a> = Vec<HexDirection>;
+    type Answer1 = isize;
+    type Answer2 = isize;
+
+    fn parse(input: &str) -> Result<Vec<HexDirection>, Error> {
+        input
+            .trim()
+            .split(',')
+            .filter(|token| !token.is_empty())
+            .map(|token| token.trim().parse().map_err(Error::parse))
+            .collect()
+    }
+
+    fn part1(directions: &Vec<HexDirection>) -> Option<isize> {
+        Some(directions.iter().sum::<HexPosition>().min_steps_to_origin())
+    }
+
+    fn part2(directions: &Vec<HexDirection>) -> Option<isize> {
+        directions
+            .iter()
+            .scan(HexPosition::new(), |position, direction| {
+                *position = *position + direction;
+                Some(position.min_steps_to_origin())
+            })
+            .max()
+    }
+}
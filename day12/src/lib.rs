@@ -1,7 +1,11 @@
+#[allow(warnings, clippy::all)]
 pub mod parser;
 pub use parser::parse_connections;
 
-type Connections = (usize, Vec<usize>);
+extern crate util;
+use util::{Error, Solution};
+
+pub type Connections = (usize, Vec<usize>);
 type Graph = Vec<bool>;
 
 fn map_connections(graph: &mut Graph, connections: &[Connections], node: usize) {
@@ -43,7 +47,7 @@ pub fn connected_to_zero(connections: &[Connections]) -> Vec<usize> {
         "Input connections must be sorted by node index"
     );
     let mut graph = vec![false; connections.len()];
-    if graph.len() > 0 {
+    if !graph.is_empty() {
         map_connections(&mut graph, connections, 0)
     }
     graph
@@ -79,3 +83,35 @@ pub fn count_groups(connections: &[Connections]) -> usize {
 
     group
 }
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input<'a> = Vec<Connections>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<Connections>, Error> {
+        let connections = input
+            .trim()
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| {
+                parse_connections(line)
+                    .map_err(|e| Error::parse(format!("line {}: {:?}", idx + 1, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !connections.iter().enumerate().all(|(idx, cxn)| idx == cxn.0) {
+            return Err(Error::parse("Input connections must be sorted by node index"));
+        }
+        Ok(connections)
+    }
+
+    fn part1(connections: &Vec<Connections>) -> Option<usize> {
+        Some(connected_to_zero(connections).len())
+    }
+
+    fn part2(connections: &Vec<Connections>) -> Option<usize> {
+        Some(count_groups(connections))
+    }
+}
@@ -3,12 +3,20 @@ use std::cmp::max;
 
 #[macro_use]
 extern crate util;
+use util::{Error, Solution};
+use util::stream::rows_as_by;
 
 pub struct Firewall {
     layer_depths: HashMap<usize, usize>,
     max_layer: usize,
 }
 
+impl Default for Firewall {
+    fn default() -> Firewall {
+        Firewall::new()
+    }
+}
+
 impl Firewall {
     pub fn new() -> Firewall {
         Firewall {
@@ -56,7 +64,7 @@ impl Firewall {
     /// Note that for unsafe inputs, this will run until it overflows
     /// usize, at which point it will panic.
     pub fn find_first_uncaught_delay(&self) -> usize {
-        for delay in 0..usize::max_value() {
+        for delay in 0..usize::MAX {
             debug_println!("Testing a delay of {}:", delay);
             if self.__traversal_severity(delay, true) == 0 {
                 return delay;
@@ -92,3 +100,34 @@ fn scanner_position(range: usize, time: usize) -> usize {
         scanner_period(range) - period_position
     }
 }
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input<'a> = Firewall;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Firewall, Error> {
+        let mut firewall = Firewall::new();
+        let mut rules = rows_as_by::<_, _, usize>(input.as_bytes(), |line| {
+            line.split(": ").collect()
+        });
+        while let Some(rule) = rules.next() {
+            let rule = rule?;
+            if rule.len() != 2 {
+                return Err(rules.error_at_line("rule must have 2 parts"));
+            }
+            firewall.add_layer(rule[0], rule[1]);
+        }
+        Ok(firewall)
+    }
+
+    fn part1(firewall: &Firewall) -> Option<usize> {
+        Some(firewall.traversal_severity(0))
+    }
+
+    fn part2(firewall: &Firewall) -> Option<usize> {
+        Some(firewall.find_first_uncaught_delay())
+    }
+}
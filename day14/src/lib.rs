@@ -1,5 +1,6 @@
 #[macro_use]
 extern crate util;
+use util::{Error, Solution};
 
 extern crate day10;
 use day10::hash;
@@ -97,7 +98,7 @@ struct Regional {
 impl Regional {
     fn new(value: bool) -> Regional {
         Regional {
-            value: value,
+            value,
             region: None,
         }
     }
@@ -109,7 +110,7 @@ fn to_rfield(bv: Vec<Vec<bool>>) -> Rfield {
     let mut rv = Vec::with_capacity(bv.len());
     rv.extend(bv.into_iter().map(|row| {
         let mut r_row = Vec::with_capacity(row.len());
-        r_row.extend(row.into_iter().map(|v| Regional::new(v)));
+        r_row.extend(row.into_iter().map(Regional::new));
         r_row
     }));
     rv
@@ -146,3 +147,27 @@ pub fn region_count(key: &str) -> usize {
     }
     group
 }
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input<'a> = &'a str;
+    type Answer1 = u32;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<&str, Error> {
+        let key = input.trim();
+        if key.is_empty() {
+            return Err(Error::parse("empty key"));
+        }
+        Ok(key)
+    }
+
+    fn part1(key: &&str) -> Option<u32> {
+        Some(count_bits_of_hashes_for(key))
+    }
+
+    fn part2(key: &&str) -> Option<usize> {
+        Some(region_count(key))
+    }
+}
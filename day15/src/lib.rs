@@ -1,3 +1,6 @@
+extern crate util;
+use util::{Error, Solution};
+
 pub struct Generator {
     prev_value: u64,
     factor: u64,
@@ -16,8 +19,8 @@ impl Generator {
     pub fn new(prev: u64, factor: u64, quotrem: u64) -> Generator {
         Generator {
             prev_value: prev,
-            factor: factor,
-            quotrem: quotrem,
+            factor,
+            quotrem,
         }
     }
 
@@ -42,3 +45,46 @@ where
         .filter(|&(a, b)| a & LOW_16 == b & LOW_16)
         .count()
 }
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    /// The initial values of generators A and B
+    type Input<'a> = (u64, u64);
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<(u64, u64), Error> {
+        let seeds = input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                line.split_whitespace()
+                    .last()
+                    .ok_or_else(|| Error::parse("empty generator line"))?
+                    .parse()
+                    .map_err(Error::parse)
+            })
+            .collect::<Result<Vec<u64>, _>>()?;
+        if seeds.len() != 2 {
+            return Err(Error::parse("expected exactly 2 generators"));
+        }
+        Ok((seeds[0], seeds[1]))
+    }
+
+    fn part1(&(a, b): &(u64, u64)) -> Option<usize> {
+        Some(judge(
+            Generator::generator_a(a),
+            Generator::generator_b(b),
+            40_000_000,
+        ))
+    }
+
+    fn part2(&(a, b): &(u64, u64)) -> Option<usize> {
+        Some(judge(
+            Generator::generator_a(a).filter(|&v| v % 4 == 0),
+            Generator::generator_b(b).filter(|&v| v % 8 == 0),
+            5_000_000,
+        ))
+    }
+}
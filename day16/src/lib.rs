@@ -1,6 +1,9 @@
 use std::str::FromStr;
 use std::collections::VecDeque;
 
+extern crate util;
+use util::{Error, Solution};
+
 pub const MIN_CHAR: char = 'a';
 pub const MAX_CHAR: char = 'p';
 
@@ -34,8 +37,8 @@ impl FromStr for Instruction {
                 Ok(Exchange(a, b))
             }
             'p' => {
-                let bstr = s[1..].as_bytes();
-                if bstr.len() == 3 && bstr[1] == '/' as u8 && bstr[0] >= MIN_CHAR as u8 &&
+                let bstr = &s.as_bytes()[1..];
+                if bstr.len() == 3 && bstr[1] == b'/' && bstr[0] >= MIN_CHAR as u8 &&
                     bstr[0] <= MAX_CHAR as u8 && bstr[2] >= MIN_CHAR as u8 &&
                     bstr[2] <= MAX_CHAR as u8
                 {
@@ -176,10 +179,34 @@ fn apply_translation<T>(
         buffer[t_idx] = positions[p_idx];
     }
 
-    ::std::mem::swap(positions, &mut buffer);
+    ::std::mem::swap(positions, buffer);
 }
 
 
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input<'a> = Vec<Instruction>;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Vec<Instruction>, Error> {
+        input
+            .trim()
+            .split(',')
+            .map(|token| token.trim().parse().map_err(Error::parse))
+            .collect()
+    }
+
+    fn part1(moves: &Vec<Instruction>) -> Option<String> {
+        Some(dance(moves))
+    }
+
+    fn part2(moves: &Vec<Instruction>) -> Option<String> {
+        Some(dance_repeat(moves, 1_000_000_000))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -190,7 +217,7 @@ mod tests {
     /// This is the same code ase the first portion of the next test, copy-pasted.
     /// The goal is to prove that it is only the second assertion which fails.
     fn test_translation_functsions_work() {
-        println!("");
+        println!();
         let instructions = {
             use Instruction::*;
             [Spin(1), Exchange(3, 4), Partner('e', 'b')]
@@ -220,7 +247,7 @@ mod tests {
     /// The second assert_eq shows that dancing again, and translating again,
     /// produce different results.
     fn test_translation_table_fails() {
-        println!("");
+        println!();
         let instructions = {
             use Instruction::*;
             [Spin(1), Exchange(3, 4), Partner('e', 'b')]
@@ -3,19 +3,28 @@ pub const FIFTY_MILLION: usize = 50_000_000;
 
 type List<T> = Vec<T>;
 
+extern crate util;
+use util::{Error, Solution};
+
 pub struct Spinner {
     index: usize,
     items: List<usize>,
     next_insert: usize,
 }
 
+impl Default for Spinner {
+    fn default() -> Spinner {
+        Spinner::new()
+    }
+}
+
 impl Spinner {
     pub fn new() -> Spinner {
         let mut items = List::with_capacity(FIFTY_MILLION);
         items.insert(0, 0);
         Spinner {
             index: 0,
-            items: items,
+            items,
             next_insert: 1,
         }
     }
@@ -48,6 +57,12 @@ pub struct FastSpinner {
     after_zero: usize,
 }
 
+impl Default for FastSpinner {
+    fn default() -> FastSpinner {
+        FastSpinner::new()
+    }
+}
+
 impl FastSpinner {
     pub fn new() -> FastSpinner {
         FastSpinner {
@@ -82,6 +97,31 @@ impl FastSpinner {
     }
 }
 
+pub struct Day17;
+
+impl Solution for Day17 {
+    /// The number of steps the spinlock takes between insertions
+    type Input<'a> = usize;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<usize, Error> {
+        input.trim().parse().map_err(Error::parse)
+    }
+
+    fn part1(&steps: &usize) -> Option<usize> {
+        let mut spinner = Spinner::new();
+        spinner.insert_until(TWENTY_SEVENTEEN, steps);
+        spinner.get_items().get(spinner.get_index() + 1).cloned()
+    }
+
+    fn part2(&steps: &usize) -> Option<usize> {
+        let mut spinner = FastSpinner::new();
+        spinner.insert_until(FIFTY_MILLION, steps);
+        Some(spinner.get_after_zero())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,29 +1,21 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
 use std::sync::RwLock;
 
-pub mod parser;
-
-pub type RegisterID = char;
-pub type RegisterValue = i64;
-type Registers = HashMap<RegisterID, RegisterValue>;
-type Queue = Rc<RwLock<VecDeque<RegisterValue>>>;
+extern crate util;
+use util::{Error, Solution};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ValueHolder {
-    Register(RegisterID),
-    Number(RegisterValue),
-}
+extern crate vm;
+pub use vm::channel::{Report, Summary};
+pub use vm::{
+    Arithmetic, Breakpoint, ChannelIo, MachineError, Op, Profile, Queue, QueueIo, RegisterID,
+    RegisterValue, Snapshot, Step, Stop, ValueHolder,
+};
 
-impl ValueHolder {
-    fn value(&self, registers: &mut Registers) -> RegisterValue {
-        use ValueHolder::*;
-        match *self {
-            Register(id) => *registers.entry(id).or_insert(0),
-            Number(value) => value,
-        }
-    }
-}
+#[allow(warnings, clippy::all)]
+pub mod parser;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Instruction {
@@ -36,18 +28,30 @@ pub enum Instruction {
     Jgz(ValueHolder, ValueHolder),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum MachineError {
-    IPOutOfBounds,
-    ReadBlock,
+impl vm::Instruction for Instruction {
+    fn op(&self) -> Op {
+        use Instruction::*;
+        match *self {
+            Snd(x) => Op::Snd(x),
+            Set(x, y) => Op::Set(x, y),
+            Add(x, y) => Op::Add(x, y),
+            Mul(x, y) => Op::Mul(x, y),
+            Mod(x, y) => Op::Mod(x, y),
+            Rcv(x) => Op::Rcv(x),
+            Jgz(x, y) => Op::Jgz(x, y),
+        }
+    }
 }
 
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", vm::Instruction::op(self))
+    }
+}
+
+/// A day18 program, communicating over shared queues
 pub struct Machine<'instructions> {
-    instructions: &'instructions [Instruction],
-    instruction_pointer: RegisterValue,
-    registers: Registers,
-    send_q: Queue,
-    recv_q: Queue,
+    machine: vm::Machine<'instructions, Instruction, QueueIo>,
 }
 
 impl<'instructions> Machine<'instructions> {
@@ -58,81 +62,16 @@ impl<'instructions> Machine<'instructions> {
         recv_q: Queue,
     ) -> Machine<'instructions> {
         Machine {
-            instructions: instructions,
-            instruction_pointer: 0,
-            registers: {
-                let mut h = HashMap::new();
-                h.insert('p', id);
-                h
-            },
-            send_q: send_q,
-            recv_q: recv_q,
+            machine: vm::Machine::with_io(instructions, QueueIo::new(send_q, recv_q))
+                .with_register('p', id),
         }
     }
 
-    /// Execute a single instruction
-    fn execute(&mut self) -> Result<(Instruction, RegisterValue), MachineError> {
-        if !(self.instruction_pointer >= 0 &&
-                 (self.instruction_pointer as usize) < self.instructions.len())
-        {
-            return Err(MachineError::IPOutOfBounds);
+    /// Choose how overflowing results are handled; by default, they're errors
+    pub fn with_arithmetic(self, arithmetic: Arithmetic) -> Machine<'instructions> {
+        Machine {
+            machine: self.machine.with_arithmetic(arithmetic),
         }
-        let (ip, rv) = {
-            use Instruction::*;
-            match self.instructions[self.instruction_pointer as usize] {
-                Snd(messageh) => {
-                    let message = messageh.value(&mut self.registers);
-                    self.send_q
-                        .write()
-                        .expect("couldn't get send lock")
-                        .push_back(message);
-                    (self.instruction_pointer + 1, (Snd(messageh), message))
-                }
-                Set(id, valueh) => {
-                    let value = valueh.value(&mut self.registers);
-                    self.registers.insert(id, value);
-                    (self.instruction_pointer + 1, (Set(id, valueh), value))
-                }
-                Add(id, valueh) => {
-                    let value = valueh.value(&mut self.registers);
-                    *self.registers.entry(id).or_insert(0) += value;
-                    (self.instruction_pointer + 1, (Add(id, valueh), value))
-                }
-                Mul(id, valueh) => {
-                    let value = valueh.value(&mut self.registers);
-                    *self.registers.entry(id).or_insert(0) *= value;
-                    (self.instruction_pointer + 1, (Mul(id, valueh), value))
-                }
-                Mod(id, valueh) => {
-                    let value = valueh.value(&mut self.registers);
-                    *self.registers.entry(id).or_insert(0) %= value;
-                    (self.instruction_pointer + 1, (Mod(id, valueh), value))
-                }
-                Rcv(id) => {
-                    if let Some(message) = self.recv_q
-                        .write()
-                        .expect("couldn't get receive lock")
-                        .pop_front()
-                    {
-                        self.registers.insert(id, message);
-                        (self.instruction_pointer + 1, (Rcv(id), message))
-                    } else {
-                        return Err(MachineError::ReadBlock);
-                    }
-                }
-                Jgz(x, y) => {
-                    if x.value(&mut self.registers) > 0 {
-                        let yval = y.value(&mut self.registers);
-                        let dest = self.instruction_pointer + yval;
-                        (dest, (Jgz(x, y), yval))
-                    } else {
-                        (self.instruction_pointer + 1, (Jgz(x, y), 1))
-                    }
-                }
-            }
-        };
-        self.instruction_pointer = ip;
-        Ok(rv)
     }
 
     pub fn run(&mut self) -> Option<RegisterValue> {
@@ -145,6 +84,38 @@ impl<'instructions> Machine<'instructions> {
     }
 }
 
+impl<'instructions> Deref for Machine<'instructions> {
+    type Target = vm::Machine<'instructions, Instruction, QueueIo>;
+    fn deref(&self) -> &Self::Target {
+        &self.machine
+    }
+}
+
+impl<'instructions> DerefMut for Machine<'instructions> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.machine
+    }
+}
+
+/// Interpret `snd` as playing a sound and `rcv` as recovering the last sound played.
+///
+/// Returns the first frequency recovered by a `rcv` whose register is nonzero.
+pub fn recover_frequency(instructions: &[Instruction]) -> Option<RegisterValue> {
+    let sounds = Rc::new(RwLock::new(VecDeque::new()));
+    let recovered = Rc::new(RwLock::new(VecDeque::new()));
+    let mut machine = Machine::new(instructions, 0, sounds.clone(), recovered.clone());
+    loop {
+        if let Instruction::Rcv(id) = *machine.current()? {
+            if machine.register(id) != 0 {
+                return sounds.read().expect("couldn't get sound lock").back().cloned();
+            }
+            // a zero-valued rcv is a no-op: feed the register its own value back
+            recovered.write().expect("couldn't get recover lock").push_back(0);
+        }
+        machine.execute().ok()?;
+    }
+}
+
 pub struct Duet<'instructions> {
     machine_0: Machine<'instructions>,
     machine_1: Machine<'instructions>,
@@ -152,29 +123,268 @@ pub struct Duet<'instructions> {
 }
 
 impl<'instructions> Duet<'instructions> {
-    pub fn new(instructions: &'instructions [Instruction]) -> Duet {
+    pub fn new(instructions: &'instructions [Instruction]) -> Duet<'instructions> {
         let queue_0 = Rc::new(RwLock::new(VecDeque::new()));
         let queue_1 = Rc::new(RwLock::new(VecDeque::new()));
         let machine_0 = Machine::new(instructions, 0, queue_1.clone(), queue_0.clone());
         let machine_1 = Machine::new(instructions, 1, queue_0.clone(), queue_1.clone());
         Duet {
-            machine_0: machine_0,
-            machine_1: machine_1,
+            machine_0,
+            machine_1,
             send_count_1: 0,
         }
     }
 
-    pub fn run(&mut self) -> usize {
+    /// Run both programs until neither can continue, returning how many messages program 1 sent
+    ///
+    /// Fails if either program stops for any reason but blocking or halting,
+    /// such as an overflow.
+    pub fn run(&mut self) -> Result<usize, MachineError> {
         loop {
             let result_0 = self.machine_0.execute();
             let result_1 = self.machine_1.execute();
             if let Ok((Instruction::Snd(_), _)) = result_1 {
                 self.send_count_1 += 1;
             }
+            for result in &[result_0, result_1] {
+                match *result {
+                    Err(MachineError::ReadBlock) | Err(MachineError::IPOutOfBounds) | Ok(_) => {}
+                    Err(err) => return Err(err),
+                }
+            }
             if result_0.is_err() && result_1.is_err() {
                 break;
             }
         }
-        self.send_count_1
+        Ok(self.send_count_1)
+    }
+}
+
+/// How the nodes of a `Network` are connected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Topology {
+    /// Each node sends to the next; the last sends to the first
+    Ring(usize),
+    /// Node 0 sends to every other node, and each of them sends only to node 0
+    Star(usize),
+    /// The `i`th list names the nodes whose `rcv` is fed by node `i`'s `snd`
+    Adjacency(Vec<Vec<usize>>),
+}
+
+impl Topology {
+    pub fn nodes(&self) -> usize {
+        match *self {
+            Topology::Ring(n) | Topology::Star(n) => n,
+            Topology::Adjacency(ref edges) => edges.len(),
+        }
+    }
+
+    /// For each node, the nodes it sends to
+    pub fn edges(&self) -> Vec<Vec<usize>> {
+        match *self {
+            Topology::Ring(n) => (0..n).map(|node| vec![(node + 1) % n]).collect(),
+            Topology::Star(n) => (0..n)
+                .map(|node| if node == 0 { (1..n).collect() } else { vec![0] })
+                .collect(),
+            Topology::Adjacency(ref edges) => edges.clone(),
+        }
+    }
+}
+
+/// Any number of day18 programs, each on its own thread, connected by channels
+///
+/// Each node's `p` register is initialized to its index. A message sent by a node
+/// goes to every node it's connected to.
+pub struct Network<'instructions> {
+    machines: Vec<vm::Machine<'instructions, Instruction, ChannelIo>>,
+}
+
+impl<'instructions> Network<'instructions> {
+    /// Run a different program on each node
+    pub fn new(
+        topology: &Topology,
+        programs: &[&'instructions [Instruction]],
+    ) -> Result<Network<'instructions>, &'static str> {
+        let edges = topology.edges();
+        if programs.len() != edges.len() {
+            return Err("need exactly one program per node");
+        }
+        if edges.iter().flatten().any(|&target| target >= edges.len()) {
+            return Err("edge to a node outside the network");
+        }
+        let machines = ChannelIo::network(&edges)
+            .into_iter()
+            .zip(programs)
+            .enumerate()
+            .map(|(id, (io, program))| {
+                vm::Machine::with_io(program, io).with_register('p', id as RegisterValue)
+            })
+            .collect();
+        Ok(Network { machines })
+    }
+
+    /// Run the same program on every node
+    pub fn uniform(
+        topology: &Topology,
+        instructions: &'instructions [Instruction],
+    ) -> Result<Network<'instructions>, &'static str> {
+        Network::new(topology, &vec![instructions; topology.nodes()])
+    }
+
+    /// Run every node until it halts or the network deadlocks
+    pub fn run(self) -> Summary {
+        vm::channel::run(self.machines)
+    }
+}
+
+/// A duet whose programs each run on their own thread, communicating over channels
+///
+/// Unlike `Duet`, deadlock is detected globally: the run ends once both programs
+/// are blocked on empty queues, or one has halted and the other is blocked.
+pub struct ThreadedDuet<'instructions> {
+    network: Network<'instructions>,
+}
+
+impl<'instructions> ThreadedDuet<'instructions> {
+    pub fn new(instructions: &'instructions [Instruction]) -> ThreadedDuet<'instructions> {
+        ThreadedDuet {
+            network: Network::uniform(&Topology::Ring(2), instructions)
+                .expect("a ring is always a valid topology"),
+        }
+    }
+
+    /// Run both programs to completion, reporting how many messages each sent and received
+    pub fn run(self) -> Summary {
+        self.network.run()
+    }
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input<'a> = Vec<Instruction>;
+    type Answer1 = RegisterValue;
+    type Answer2 = usize;
+
+    /// Jumps may target labels, as written by `vm::disassemble`
+    fn parse(input: &str) -> Result<Vec<Instruction>, Error> {
+        vm::parse_lines(input, parser::parse_instruction).map_err(Error::parse)
+    }
+
+    fn part1(instructions: &Vec<Instruction>) -> Option<RegisterValue> {
+        recover_frequency(instructions)
+    }
+
+    fn part2(instructions: &Vec<Instruction>) -> Option<usize> {
+        Duet::new(instructions).run().ok()
+    }
+}
+
+#[cfg(test)]
+extern crate proptest;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use vm::strategy::{register, value};
+
+    const DUET: &str = "snd 1
+snd 2
+snd p
+rcv a
+rcv b
+rcv c
+rcv d";
+
+    #[test]
+    fn test_threaded_duet() {
+        let instructions = Day18::parse(DUET).unwrap();
+        let summary = ThreadedDuet::new(&instructions).run();
+        assert!(summary.deadlocked);
+        assert_eq!(Ok(summary.machines[1].sent), Duet::new(&instructions).run());
+        for report in &summary.machines {
+            assert_eq!((report.sent, report.received), (3, 3));
+            assert_eq!(report.stopped, MachineError::ReadBlock);
+        }
+    }
+
+    #[test]
+    fn test_duet_overflow() {
+        // program 1 overflows instead of blocking, which isn't a deadlock
+        let instructions = Day18::parse(
+            "jgz p 2
+rcv a
+set a 9223372036854775807
+add a p
+rcv a",
+        )
+        .unwrap();
+        assert_eq!(
+            Duet::new(&instructions).run(),
+            Err(MachineError::Overflow { ip: 3 })
+        );
+        assert_eq!(Day18::part2(&instructions), None);
+    }
+
+    #[test]
+    fn test_topology_edges() {
+        assert_eq!(Topology::Ring(3).edges(), vec![vec![1], vec![2], vec![0]]);
+        assert_eq!(
+            Topology::Star(3).edges(),
+            vec![vec![1, 2], vec![0], vec![0]]
+        );
+    }
+
+    #[test]
+    fn test_star_network() {
+        // the hub broadcasts one message; each spoke replies once and halts
+        let hub = Day18::parse("snd 7\nrcv a\nrcv a\nrcv a").unwrap();
+        let spoke = Day18::parse("rcv a\nsnd p").unwrap();
+        let summary = Network::new(&Topology::Star(3), &[&hub, &spoke, &spoke])
+            .unwrap()
+            .run();
+        assert!(!summary.terminated());
+        assert_eq!(summary.deadlocked_machines(), vec![0]);
+        let traffic = summary
+            .machines
+            .iter()
+            .map(|report| (report.sent, report.received))
+            .collect::<Vec<_>>();
+        assert_eq!(traffic, vec![(2, 2), (1, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn test_network_validates_topology() {
+        let instructions = Day18::parse(DUET).unwrap();
+        let dangling = Topology::Adjacency(vec![vec![2], vec![0]]);
+        assert!(Network::uniform(&dangling, &instructions).is_err());
+        assert!(Network::new(&Topology::Ring(3), &[&instructions]).is_err());
+    }
+
+    fn instruction() -> impl Strategy<Value = Instruction> {
+        use Instruction::*;
+        prop_oneof![
+            value().prop_map(Snd),
+            (register(), value()).prop_map(|(x, y)| Set(x, y)),
+            (register(), value()).prop_map(|(x, y)| Add(x, y)),
+            (register(), value()).prop_map(|(x, y)| Mul(x, y)),
+            (register(), value()).prop_map(|(x, y)| Mod(x, y)),
+            register().prop_map(Rcv),
+            (value(), value()).prop_map(|(x, y)| Jgz(x, y)),
+        ]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_round_trip(
+            instructions in prop::collection::vec(instruction(), 0..30),
+            labels in any::<bool>(),
+        ) {
+            let text = vm::disassemble(&instructions, labels);
+            prop_assert_eq!(Day18::parse(&text).unwrap(), instructions);
+        }
     }
 }
@@ -1,9 +1,10 @@
 #[macro_use]
 extern crate util;
+use util::{Error, Solution};
 
 use std::ops::Add;
 
-type Maze = Vec<Vec<char>>;
+pub type Maze = Vec<Vec<char>>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Direction {
@@ -143,8 +144,8 @@ impl<'a> LineFollower<'a> {
         };
 
         Some(LineFollower {
-            maze: maze,
-            position: position,
+            maze,
+            position,
             direction: Some(Direction::Down),
             prev_position: None,
         })
@@ -162,3 +163,43 @@ pub fn collect_letters(maze: &Maze) -> Option<(Vec<char>, usize)> {
     }
     Some((results, steps))
 }
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input<'a> = Maze;
+    type Answer1 = String;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Maze, Error> {
+        // leading spaces are significant, so the input can't be trimmed
+        let mut maze: Maze = input
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .filter(|&c| c == ' ' || !c.is_whitespace())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        while maze.last().is_some_and(|row| row.iter().all(|&c| c == ' ')) {
+            maze.pop();
+        }
+        let max_width = maze
+            .iter()
+            .map(|row| row.len())
+            .max()
+            .ok_or_else(|| Error::parse("empty maze"))?;
+        for row in &mut maze {
+            row.resize(max_width, ' ');
+        }
+        Ok(maze)
+    }
+
+    fn part1(maze: &Maze) -> Option<String> {
+        collect_letters(maze).map(|(letters, _)| letters.into_iter().collect())
+    }
+
+    fn part2(maze: &Maze) -> Option<usize> {
+        collect_letters(maze).map(|(_, steps)| steps)
+    }
+}
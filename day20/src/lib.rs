@@ -1,6 +1,10 @@
 use std::collections::HashSet;
 use std::ops::{Add, AddAssign};
 
+extern crate util;
+use util::{Error, Solution};
+
+#[allow(warnings, clippy::all)]
 pub mod parser;
 pub use parser::parse_particle;
 
@@ -13,7 +17,7 @@ pub struct Vector3 {
 
 impl Vector3 {
     pub fn new(x: i64, y: i64, z: i64) -> Vector3 {
-        Vector3 { x: x, y: y, z: z }
+        Vector3 { x, y, z }
     }
 
     pub fn abs_sum(&self) -> i64 {
@@ -77,6 +81,25 @@ impl Particle {
     }
 }
 
+/// find the index of the particle which stays closest to the origin in the long term
+///
+/// Acceleration dominates eventually; ties are broken by velocity, then position.
+pub fn closest_long_term(particles: &[Particle]) -> Option<usize> {
+    particles
+        .iter()
+        .enumerate()
+        .map(|(idx, p)| {
+            (
+                p.get_acc().abs_sum(),
+                p.get_vel().abs_sum(),
+                p.get_pos().abs_sum(),
+                idx,
+            )
+        })
+        .min()
+        .map(|(_, _, _, idx)| idx)
+}
+
 /// update the simulation state in O(n**2)
 ///
 /// not great, but should be sufficient, and I'm
@@ -87,8 +110,8 @@ pub fn destroy_tick(particles: &mut Vec<Particle>) {
     for (outer_idx, outer_particle) in particles.iter().enumerate() {
         for (inner_idx, inner_particle) in particles.iter().enumerate() {
             if outer_idx != inner_idx && outer_particle.position == inner_particle.position {
-                destroyed.insert(outer_particle.clone());
-                destroyed.insert(inner_particle.clone());
+                destroyed.insert(*outer_particle);
+                destroyed.insert(*inner_particle);
             }
         }
     }
@@ -114,3 +137,33 @@ pub fn simulate(particles: &mut Vec<Particle>) {
         old_plen = particles.len()
     }
 }
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input<'a> = Vec<Particle>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<Particle>, Error> {
+        input
+            .lines()
+            .enumerate()
+            .filter(|&(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                parse_particle(line.trim())
+                    .map_err(|e| Error::parse(format!("line {}: {:?}", idx + 1, e)))
+            })
+            .collect()
+    }
+
+    fn part1(particles: &Vec<Particle>) -> Option<usize> {
+        closest_long_term(particles)
+    }
+
+    fn part2(particles: &Vec<Particle>) -> Option<usize> {
+        let mut particles = particles.clone();
+        simulate(&mut particles);
+        Some(particles.len())
+    }
+}
@@ -1,5 +1,9 @@
 use std::collections::HashMap;
 
+extern crate util;
+use util::{Error, Solution};
+
+#[allow(warnings, clippy::all)]
 pub mod parser;
 pub use parser::parse_rule;
 
@@ -66,7 +70,7 @@ fn permute(image: &Image) -> Vec<Image> {
 
 pub fn generate_rules(rules: &[Rule]) -> Rules {
     let mut out = Rules::new();
-    for &(ref source, ref dest) in rules {
+    for (source, dest) in rules {
         for source_permutation in permute(source) {
             out.insert(source_permutation, dest.clone());
         }
@@ -77,12 +81,12 @@ pub fn generate_rules(rules: &[Rule]) -> Rules {
 pub fn enhance(rules: &Rules, image: &Image) -> Image {
     // images are always square
     let size = image.len();
-    let (in_chunk_size, out_chunk_size, out_size) = if size % 2 == 0 {
+    let (in_chunk_size, out_chunk_size, out_size) = if size.is_multiple_of(2) {
         // enhance 2-chunks
         (2, 3, (size / 2) * 3)
     } else {
         // rules should enforce that this is true
-        assert!(size % 3 == 0);
+        assert!(size.is_multiple_of(3));
         (3, 4, (size / 3) * 4)
     };
     let mut out = vec![vec![false; out_size]; out_size];
@@ -113,3 +117,44 @@ pub fn enhance(rules: &Rules, image: &Image) -> Image {
 
     out
 }
+
+/// count the pixels which are on after enhancing the base image `iterations` times
+pub fn on_after(rules: &Rules, iterations: usize) -> usize {
+    let mut image = base();
+    for _ in 0..iterations {
+        image = enhance(rules, &image);
+    }
+    image
+        .iter()
+        .map(|row| row.iter().filter(|&&pixel| pixel).count())
+        .sum()
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input<'a> = Rules;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Rules, Error> {
+        let given_rules = input
+            .lines()
+            .enumerate()
+            .filter(|&(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                parse_rule(line.trim())
+                    .map_err(|e| Error::parse(format!("line {}: {:?}", idx + 1, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(generate_rules(&given_rules))
+    }
+
+    fn part1(rules: &Rules) -> Option<usize> {
+        Some(on_after(rules, 5))
+    }
+
+    fn part2(rules: &Rules) -> Option<usize> {
+        Some(on_after(rules, 18))
+    }
+}
@@ -1,24 +1,21 @@
 use std::fmt;
 
+#[allow(unused_imports)]
 #[macro_use]
 extern crate util;
+use util::{Error, Solution};
 
 pub type Map = Vec<Vec<NodeState>>;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Direction {
+    #[default]
     Up,
     Right,
     Down,
     Left,
 }
 
-impl Default for Direction {
-    fn default() -> Direction {
-        Direction::Up
-    }
-}
-
 impl Direction {
     pub fn right(&self) -> Direction {
         use Direction::*;
@@ -69,25 +66,47 @@ impl NodeState {
             Flagged => Clean,
         }
     }
+
+    /// Touch a node as the original, unevolved virus does
+    pub fn toggle(&self) -> NodeState {
+        use NodeState::*;
+        match *self {
+            Clean => Infected,
+            _ => Clean,
+        }
+    }
 }
 
 pub const MAP_FACTOR: usize = 500;
 
-pub fn generate_map(input: &str) -> Map {
-    let lines = input.lines()
+/// Parse the initial grid of nodes, without any surrounding space
+pub fn parse_grid(input: &str) -> Result<Map, &'static str> {
+    let grid = input
+        .lines()
         .map(|line| line.trim())
-        .filter(|&line| line.len() > 0)
+        .filter(|&line| !line.is_empty())
         .map(|line| {
             line.chars()
                 .map(|c| match c {
-                    '.' => NodeState::Clean,
-                    '#' => NodeState::Infected,
-                    _ => panic!("unexpected char in input"),
+                    '.' => Ok(NodeState::Clean),
+                    '#' => Ok(NodeState::Infected),
+                    _ => Err("unexpected char in input"),
                 })
-                .collect::<Vec<_>>()
+                .collect::<Result<Vec<_>, _>>()
         })
-        .collect::<Vec<_>>();
-    let nr_rows = lines.len();
+        .collect::<Result<Vec<_>, _>>()?;
+    if grid.is_empty() {
+        return Err("empty grid");
+    }
+    if grid.iter().any(|row| row.len() != grid.len()) {
+        return Err("grid must be square");
+    }
+    Ok(grid)
+}
+
+/// Surround a grid with enough clean space for the virus to wander in
+pub fn expand_map(grid: &Map) -> Map {
+    let nr_rows = grid.len();
     let map_size = nr_rows * MAP_FACTOR + 1;
 
     let mut out = Vec::with_capacity(map_size);
@@ -96,12 +115,10 @@ pub fn generate_map(input: &str) -> Map {
     for _ in 0..nr_prefix_rows {
         out.push(vec![NodeState::Clean; map_size]);
     }
-    for row_idx in 0..nr_rows {
+    for line in grid.iter() {
         let mut row = vec![NodeState::Clean; map_size];
         // assume a square input
-        for col_idx in 0..nr_rows {
-            row[col_idx + nr_prefix_rows] = lines[row_idx][col_idx];
-        }
+        row[nr_prefix_rows..(nr_prefix_rows + nr_rows)].copy_from_slice(&line[..nr_rows]);
         out.push(row);
     }
     for _ in 0..nr_prefix_rows {
@@ -111,11 +128,16 @@ pub fn generate_map(input: &str) -> Map {
     out
 }
 
+pub fn generate_map(input: &str) -> Map {
+    expand_map(&parse_grid(input).expect("couldn't parse grid"))
+}
+
 pub struct Sporifica<'a> {
     row: usize,
     col: usize,
     facing: Direction,
     map: &'a mut Map,
+    evolved: bool,
 }
 
 impl<'a> Sporifica<'a> {
@@ -125,7 +147,16 @@ impl<'a> Sporifica<'a> {
             row: half,
             col: half,
             facing: Direction::Up,
-            map: map,
+            map,
+            evolved: true,
+        }
+    }
+
+    /// A virus which only toggles nodes between clean and infected
+    pub fn unevolved(map: &'a mut Map) -> Sporifica<'a> {
+        Sporifica {
+            evolved: false,
+            ..Sporifica::new(map)
         }
     }
 
@@ -143,7 +174,11 @@ impl<'a> Sporifica<'a> {
                 Flagged => self.facing.reverse(),
             };
         }
-        *self.current() = self.current().touch();
+        *self.current() = if self.evolved {
+            self.current().touch()
+        } else {
+            self.current().toggle()
+        };
         let activated = *self.current() == NodeState::Infected;
         {
             use Direction::*;
@@ -159,7 +194,7 @@ impl<'a> Sporifica<'a> {
 
     pub fn burst_n(&mut self, n: usize) -> usize {
         let mut count = 0;
-        for i in 0..n {
+        for _i in 0..n {
             // debug_println!("bursts {}:", i);
             // debug_println!("activations: {}", count);
             // debug_println!("{}", self.to_string());
@@ -197,8 +232,30 @@ impl<'a> fmt::Display for Sporifica<'a> {
                     write!(f, " ")?;
                 }
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         write!(f, "{:?}", self.facing)
     }
 }
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input<'a> = Map;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Map, Error> {
+        parse_grid(input).map_err(Error::parse)
+    }
+
+    fn part1(grid: &Map) -> Option<usize> {
+        let mut map = expand_map(grid);
+        Some(Sporifica::unevolved(&mut map).burst_n(10_000))
+    }
+
+    fn part2(grid: &Map) -> Option<usize> {
+        let mut map = expand_map(grid);
+        Some(Sporifica::new(&mut map).burst_n(10_000_000))
+    }
+}
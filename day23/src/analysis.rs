@@ -0,0 +1,447 @@
+//! Static analysis of day23 programs: basic blocks, control flow, and loops
+//!
+//! `Cfg::new` splits a program into basic blocks and connects them by the jumps
+//! between them. `Cfg::loops` finds the natural loops: for every back edge, whose
+//! target dominates its source, the blocks which can reach the source without
+//! passing through the target.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use vm::Macro;
+
+use Instruction::{self, *};
+use RegisterID;
+use ValueHolder::{self, *};
+
+/// Where control may go after a basic block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Successor {
+    /// The block with this index
+    Block(usize),
+    /// Outside the program, which halts
+    Exit,
+    /// A jump by an offset held in a register, which could go anywhere
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    /// The index of the first instruction
+    pub start: usize,
+    /// The index one past the last instruction
+    pub end: usize,
+    pub successors: Vec<Successor>,
+    /// Every register any instruction in the block reads
+    pub reads: BTreeSet<RegisterID>,
+    /// Every register any instruction in the block writes
+    pub writes: BTreeSet<RegisterID>,
+}
+
+/// A natural loop: its header dominates every block in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loop {
+    /// The block through which control enters the loop
+    pub header: usize,
+    /// The blocks which jump back to the header
+    pub latches: BTreeSet<usize>,
+    /// Every block in the loop, including the header
+    pub blocks: BTreeSet<usize>,
+}
+
+fn add_read(reads: &mut BTreeSet<RegisterID>, value: ValueHolder) {
+    if let Register(id) = value {
+        reads.insert(id);
+    }
+}
+
+/// The registers an instruction reads and writes
+fn effects(instruction: Instruction) -> (BTreeSet<RegisterID>, BTreeSet<RegisterID>) {
+    let mut reads = BTreeSet::new();
+    let mut writes = BTreeSet::new();
+    match instruction {
+        Set(x, y) => {
+            add_read(&mut reads, y);
+            writes.insert(x);
+        }
+        Sub(x, y) | Mul(x, y) => {
+            reads.insert(x);
+            add_read(&mut reads, y);
+            writes.insert(x);
+        }
+        Jnz(x, y) => {
+            add_read(&mut reads, x);
+            add_read(&mut reads, y);
+        }
+        Instruction::Macro(Macro::MulSub { x, y, counter, .. }) => {
+            reads.extend(&[x, counter]);
+            add_read(&mut reads, y);
+            writes.extend(&[x, counter]);
+        }
+        Instruction::Macro(Macro::ClearIfFactor {
+            flag,
+            d,
+            e,
+            b,
+            scratch,
+            ..
+        }) => {
+            reads.extend(&[d, e]);
+            add_read(&mut reads, b);
+            writes.extend(&[flag, e, scratch]);
+        }
+        Instruction::Macro(Macro::ClearIfComposite {
+            flag,
+            d,
+            e,
+            b,
+            scratch,
+            ..
+        }) => {
+            reads.insert(d);
+            add_read(&mut reads, b);
+            writes.extend(&[flag, d, e, scratch]);
+        }
+    }
+    (reads, writes)
+}
+
+/// Where control may go after the instruction at `ip`, as instruction indices
+///
+/// `None` stands for a target which can't be known statically.
+fn next(ip: usize, instruction: Instruction) -> Vec<Option<i64>> {
+    let ip = ip as i64;
+    match instruction {
+        Jnz(Number(0), _) => vec![Some(ip + 1)],
+        Jnz(Number(_), Number(offset)) => vec![ip.checked_add(offset)],
+        Jnz(_, Number(offset)) => vec![ip.checked_add(offset), Some(ip + 1)],
+        Jnz(_, Register(_)) => vec![None, Some(ip + 1)],
+        Instruction::Macro(m) => vec![Some(ip + m.span() as i64)],
+        _ => vec![Some(ip + 1)],
+    }
+}
+
+fn is_jump(instruction: Instruction) -> bool {
+    matches!(instruction, Jnz(_, _) | Instruction::Macro(_))
+}
+
+/// The control-flow graph of a program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cfg {
+    /// In program order; the entry block is always first
+    pub blocks: Vec<BasicBlock>,
+}
+
+impl Cfg {
+    pub fn new(instructions: &[Instruction]) -> Cfg {
+        let len = instructions.len();
+        let in_program = |target: i64| target >= 0 && (target as usize) < len;
+
+        let mut leaders = BTreeSet::new();
+        leaders.insert(0);
+        for (ip, &instruction) in instructions.iter().enumerate() {
+            if is_jump(instruction) {
+                leaders.insert(ip + 1);
+                for target in next(ip, instruction).into_iter().flatten() {
+                    if in_program(target) {
+                        leaders.insert(target as usize);
+                    }
+                }
+            }
+        }
+        let starts = leaders
+            .into_iter()
+            .filter(|&start| start < len)
+            .collect::<Vec<_>>();
+        let block_at = starts
+            .iter()
+            .enumerate()
+            .map(|(block, &start)| (start, block))
+            .collect::<BTreeMap<_, _>>();
+
+        let blocks = starts
+            .iter()
+            .enumerate()
+            .map(|(idx, &start)| {
+                let end = starts.get(idx + 1).cloned().unwrap_or(len);
+                let mut reads = BTreeSet::new();
+                let mut writes = BTreeSet::new();
+                for &instruction in &instructions[start..end] {
+                    let (r, w) = effects(instruction);
+                    reads.extend(r);
+                    writes.extend(w);
+                }
+                let mut successors = next(end - 1, instructions[end - 1])
+                    .into_iter()
+                    .map(|target| match target {
+                        None => Successor::Unknown,
+                        Some(target) if in_program(target) => {
+                            Successor::Block(block_at[&(target as usize)])
+                        }
+                        Some(_) => Successor::Exit,
+                    })
+                    .collect::<Vec<_>>();
+                successors.sort();
+                successors.dedup();
+                BasicBlock {
+                    start,
+                    end,
+                    successors,
+                    reads,
+                    writes,
+                }
+            })
+            .collect();
+        Cfg { blocks }
+    }
+
+    /// The blocks which each block may pass control to directly
+    fn block_successors(&self, block: usize) -> Vec<usize> {
+        if self.blocks[block].successors.contains(&Successor::Unknown) {
+            return (0..self.blocks.len()).collect();
+        }
+        self.blocks[block]
+            .successors
+            .iter()
+            .filter_map(|successor| match *successor {
+                Successor::Block(target) => Some(target),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn predecessors(&self) -> Vec<Vec<usize>> {
+        let mut predecessors = vec![Vec::new(); self.blocks.len()];
+        for block in 0..self.blocks.len() {
+            for target in self.block_successors(block) {
+                predecessors[target].push(block);
+            }
+        }
+        predecessors
+    }
+
+    /// For each block, every block which dominates it
+    ///
+    /// Blocks unreachable from the entry have no dominators.
+    pub fn dominators(&self) -> Vec<BTreeSet<usize>> {
+        let count = self.blocks.len();
+        if count == 0 {
+            return Vec::new();
+        }
+        let predecessors = self.predecessors();
+
+        let mut reachable = BTreeSet::new();
+        let mut stack = vec![0];
+        while let Some(block) = stack.pop() {
+            if reachable.insert(block) {
+                stack.extend(self.block_successors(block));
+            }
+        }
+
+        let mut dominators = (0..count)
+            .map(|block| {
+                if block == 0 {
+                    Some(0).into_iter().collect()
+                } else if reachable.contains(&block) {
+                    reachable.clone()
+                } else {
+                    BTreeSet::new()
+                }
+            })
+            .collect::<Vec<BTreeSet<usize>>>();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for block in reachable.iter().cloned().filter(|&block| block != 0) {
+                let mut new = predecessors[block]
+                    .iter()
+                    .filter(|pred| reachable.contains(pred))
+                    .map(|&pred| dominators[pred].clone())
+                    .fold(None, |acc: Option<BTreeSet<usize>>, doms| match acc {
+                        None => Some(doms),
+                        Some(acc) => Some(acc.intersection(&doms).cloned().collect()),
+                    })
+                    .unwrap_or_default();
+                new.insert(block);
+                if new != dominators[block] {
+                    dominators[block] = new;
+                    changed = true;
+                }
+            }
+        }
+        dominators
+    }
+
+    /// Every natural loop, one per header, ordered by header
+    pub fn loops(&self) -> Vec<Loop> {
+        let dominators = self.dominators();
+        let predecessors = self.predecessors();
+        let mut loops: BTreeMap<usize, Loop> = BTreeMap::new();
+        for (latch, doms) in dominators.iter().enumerate() {
+            for header in self.block_successors(latch) {
+                if !doms.contains(&header) {
+                    continue;
+                }
+                let entry = loops.entry(header).or_insert_with(|| Loop {
+                    header,
+                    latches: BTreeSet::new(),
+                    blocks: Some(header).into_iter().collect(),
+                });
+                entry.latches.insert(latch);
+                let mut stack = vec![latch];
+                while let Some(block) = stack.pop() {
+                    if entry.blocks.insert(block) {
+                        stack.extend(predecessors[block].iter().cloned());
+                    }
+                }
+            }
+        }
+        loops.into_values().collect()
+    }
+
+    /// Render the graph in Graphviz DOT format
+    ///
+    /// Each block is labeled with its instructions and the registers it reads and
+    /// writes. Back edges of loops are dashed.
+    pub fn to_dot(&self, instructions: &[Instruction]) -> String {
+        let dominators = self.dominators();
+        let join = |registers: &BTreeSet<RegisterID>| {
+            registers
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        let mut dot = String::from("digraph cfg {\n    node [shape=box, fontname=monospace];\n");
+        for (idx, block) in self.blocks.iter().enumerate() {
+            let mut label = String::new();
+            for (ip, instruction) in instructions[block.start..block.end]
+                .iter()
+                .enumerate()
+                .map(|(offset, instruction)| (block.start + offset, instruction))
+            {
+                write!(label, "{}: {}\\l", ip, instruction).unwrap();
+            }
+            write!(
+                label,
+                "reads: {}\\lwrites: {}\\l",
+                join(&block.reads),
+                join(&block.writes)
+            )
+            .unwrap();
+            writeln!(dot, "    b{} [label=\"{}\"];", idx, label).unwrap();
+        }
+        let mut needs_exit = false;
+        for (idx, block) in self.blocks.iter().enumerate() {
+            for successor in &block.successors {
+                match *successor {
+                    Successor::Block(target) => {
+                        let style = if dominators[idx].contains(&target) {
+                            " [style=dashed]"
+                        } else {
+                            ""
+                        };
+                        writeln!(dot, "    b{} -> b{}{};", idx, target, style).unwrap();
+                    }
+                    Successor::Exit => {
+                        needs_exit = true;
+                        writeln!(dot, "    b{} -> exit;", idx).unwrap();
+                    }
+                    Successor::Unknown => {
+                        writeln!(dot, "    b{} -> unknown [style=dotted];", idx).unwrap();
+                    }
+                }
+            }
+        }
+        if needs_exit {
+            dot.push_str("    exit [shape=doublecircle];\n");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::Solution;
+    use Day23;
+
+    /// nested loops shaped like the real program's, with the innermost loop removed
+    const PROGRAM: &str = "set b 3
+set f 1
+set d 2
+set g d
+mul g b
+jnz g 2
+set f 0
+sub d -1
+set g d
+sub g b
+jnz g -7
+jnz f 2
+sub h -1
+sub b -1
+set g b
+sub g 6
+jnz g -15";
+
+    #[test]
+    fn test_blocks() {
+        let program = Day23::parse(PROGRAM).unwrap();
+        let cfg = Cfg::new(&program);
+        let bounds = cfg
+            .blocks
+            .iter()
+            .map(|block| (block.start, block.end))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bounds,
+            vec![
+                (0, 1),
+                (1, 3),
+                (3, 6),
+                (6, 7),
+                (7, 11),
+                (11, 12),
+                (12, 13),
+                (13, 17),
+            ]
+        );
+        assert_eq!(
+            cfg.blocks[2].successors,
+            vec![Successor::Block(3), Successor::Block(4)]
+        );
+        assert_eq!(
+            cfg.blocks[7].successors,
+            vec![Successor::Block(1), Successor::Exit]
+        );
+        assert_eq!(
+            cfg.blocks[2].reads,
+            ['b', 'd', 'g'].iter().cloned().collect()
+        );
+        assert_eq!(cfg.blocks[2].writes, Some('g').into_iter().collect());
+    }
+
+    #[test]
+    fn test_loops() {
+        let program = Day23::parse(PROGRAM).unwrap();
+        let loops = Cfg::new(&program).loops();
+        assert_eq!(loops.len(), 2);
+        assert_eq!(loops[0].header, 1);
+        assert_eq!(loops[0].blocks, (1..8).collect());
+        assert_eq!(loops[1].header, 2);
+        assert_eq!(loops[1].latches, Some(4).into_iter().collect());
+        assert_eq!(loops[1].blocks, (2..5).collect());
+    }
+
+    #[test]
+    fn test_dot() {
+        let program = Day23::parse("set a 1\nsub a 1\njnz a -1").unwrap();
+        let dot = Cfg::new(&program).to_dot(&program);
+        assert!(dot.contains("b1 -> b1 [style=dashed];"));
+        assert!(dot.contains("b1 -> exit;"));
+        assert!(dot.contains("1: sub a 1\\l2: jnz a -1\\lreads: a\\lwrites: a\\l"));
+    }
+}
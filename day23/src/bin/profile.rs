@@ -0,0 +1,47 @@
+extern crate day23;
+use day23::{optimize, Day23, Machine};
+
+extern crate util;
+use util::{read_file, Solution};
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "usage: profile [--optimized] [INPUT]
+
+INPUT defaults to day23/input.txt";
+
+/// Print an annotated listing of the input as it runs in debug mode
+///
+/// With `--optimized`, profile the optimized program with `a = 1` instead.
+fn main() {
+    let mut optimized = false;
+    let mut path = None;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--optimized" => optimized = true,
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(1);
+            }
+        }
+    }
+    let path = path.unwrap_or_else(|| "day23/input.txt".to_string());
+
+    let input = read_file(&path).unwrap_or_else(|err| {
+        eprintln!("couldn't read {}: {}", path, err);
+        process::exit(1);
+    });
+    let instructions = Day23::parse(&input).unwrap_or_else(|err| {
+        eprintln!("couldn't parse {}: {}", path, err);
+        process::exit(1);
+    });
+    let instructions = if optimized {
+        optimize(&instructions)
+    } else {
+        instructions
+    };
+    let (profile, _) = Machine::new(&instructions, !optimized).profile();
+    print!("{}", profile.annotate(&instructions));
+}
@@ -1,26 +1,23 @@
-use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
 
-pub mod parser;
+extern crate lalrpop_util;
 
-pub type RegisterID = char;
-pub type RegisterValue = i64;
-type Registers = HashMap<RegisterID, RegisterValue>;
+extern crate util;
+use util::{Error, Solution};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ValueHolder {
-    Register(RegisterID),
-    Number(RegisterValue),
-}
+extern crate vm;
+pub use vm::{
+    Arithmetic, Breakpoint, MachineError, Macro, Op, Profile, RegisterID, RegisterValue, Registers,
+    Snapshot, Step, Stop, ValueHolder,
+};
 
-impl ValueHolder {
-    fn value(&self, registers: &mut Registers) -> RegisterValue {
-        use ValueHolder::*;
-        match *self {
-            Register(id) => *registers.entry(id).or_insert(0),
-            Number(value) => value,
-        }
-    }
-}
+pub mod analysis;
+pub mod optimize;
+#[allow(warnings, clippy::all)]
+pub mod parser;
+pub use analysis::Cfg;
+pub use optimize::optimize;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Instruction {
@@ -28,89 +25,211 @@ pub enum Instruction {
     Sub(RegisterID, ValueHolder),
     Mul(RegisterID, ValueHolder),
     Jnz(ValueHolder, ValueHolder),
+    /// Usually produced by the optimizer, though it can also be parsed
+    Macro(Macro),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum MachineError {
-    IPOutOfBounds,
-    ReadBlock,
+impl vm::Instruction for Instruction {
+    fn op(&self) -> Op {
+        use Instruction::*;
+        match *self {
+            Set(x, y) => Op::Set(x, y),
+            Sub(x, y) => Op::Sub(x, y),
+            Mul(x, y) => Op::Mul(x, y),
+            Jnz(x, y) => Op::Jnz(x, y),
+            Macro(m) => Op::Macro(m),
+        }
+    }
 }
 
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", vm::Instruction::op(self))
+    }
+}
+
+/// A day23 coprocessor program
 pub struct Machine<'instructions> {
-    instructions: &'instructions [Instruction],
-    instruction_pointer: RegisterValue,
-    registers: Registers,
+    machine: vm::Machine<'instructions, Instruction>,
 }
 
 impl<'instructions> Machine<'instructions> {
+    /// In debug mode, register `a` starts at 0; otherwise, it starts at 1.
     pub fn new(instructions: &'instructions [Instruction], debug: bool) -> Machine<'instructions> {
+        let mut machine = vm::Machine::new(instructions);
+        if !debug {
+            machine = machine.with_register('a', 1);
+        }
+        Machine { machine }
+    }
+
+    /// Choose how overflowing results are handled; by default, they're errors
+    pub fn with_arithmetic(self, arithmetic: Arithmetic) -> Machine<'instructions> {
         Machine {
-            instructions: instructions,
-            instruction_pointer: 0,
-            registers: {
-                let mut h = HashMap::new();
-                if !debug {
-                    h.insert('a', 1);
-                }
-                h
-            },
+            machine: self.machine.with_arithmetic(arithmetic),
         }
     }
 
-    /// Execute a single instruction
-    fn execute(&mut self) -> Result<(Instruction, RegisterValue), MachineError> {
-        if !(self.instruction_pointer >= 0 &&
-             (self.instruction_pointer as usize) < self.instructions.len()) {
-            return Err(MachineError::IPOutOfBounds);
+    pub fn count_mul(&mut self) -> usize {
+        let (profile, _) = self.profile();
+        profile.opcodes.get("mul").cloned().unwrap_or(0) as usize
+    }
+
+    /// Execute instructions until the machine stops, returning why
+    pub fn run(&mut self) -> MachineError {
+        self.machine.run()
+    }
+}
+
+impl<'instructions> Deref for Machine<'instructions> {
+    type Target = vm::Machine<'instructions, Instruction>;
+    fn deref(&self) -> &Self::Target {
+        &self.machine
+    }
+}
+
+impl<'instructions> DerefMut for Machine<'instructions> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.machine
+    }
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input<'a> = Vec<Instruction>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    /// Jumps may target labels, as written by `vm::disassemble`
+    fn parse(input: &str) -> Result<Vec<Instruction>, Error> {
+        vm::parse_lines(input, parser::parse_instruction).map_err(Error::parse)
+    }
+
+    fn part1(instructions: &Vec<Instruction>) -> Option<usize> {
+        Some(Machine::new(instructions, true).count_mul())
+    }
+
+    fn part2(instructions: &Vec<Instruction>) -> Option<usize> {
+        let optimized = optimize(instructions);
+        let mut machine = Machine::new(&optimized, false);
+        match machine.run() {
+            MachineError::IPOutOfBounds => Some(machine.register('h') as usize),
+            _ => None,
         }
-        let (ip, rv) = {
-            use Instruction::*;
-            match self.instructions[self.instruction_pointer as usize] {
-                Set(id, valueh) => {
-                    let value = valueh.value(&mut self.registers);
-                    self.registers.insert(id, value);
-                    (self.instruction_pointer + 1, (Set(id, valueh), value))
-                }
-                Sub(id, valueh) => {
-                    let value = valueh.value(&mut self.registers);
-                    *self.registers.entry(id).or_insert(0) -= value;
-                    (self.instruction_pointer + 1, (Sub(id, valueh), value))
-                }
-                Mul(id, valueh) => {
-                    let value = valueh.value(&mut self.registers);
-                    *self.registers.entry(id).or_insert(0) *= value;
-                    (self.instruction_pointer + 1, (Mul(id, valueh), value))
+    }
+}
+
+#[cfg(test)]
+extern crate proptest;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use vm::strategy::{register, value};
+
+    fn macro_op() -> impl Strategy<Value = Macro> {
+        let span = 1..20usize;
+        prop_oneof![
+            (register(), value(), register(), span.clone()).prop_map(|(x, y, counter, span)| {
+                Macro::MulSub {
+                    x,
+                    y,
+                    counter,
+                    span,
                 }
-                Jnz(x, y) => {
-                    if x.value(&mut self.registers) != 0 {
-                        let yval = y.value(&mut self.registers);
-                        let dest = self.instruction_pointer + yval;
-                        (dest, (Jnz(x, y), yval))
-                    } else {
-                        (self.instruction_pointer + 1, (Jnz(x, y), 1))
+            }),
+            (
+                (register(), register(), register()),
+                value(),
+                register(),
+                span.clone()
+            )
+                .prop_map(|((flag, d, e), b, scratch, span)| Macro::ClearIfFactor {
+                    flag,
+                    d,
+                    e,
+                    b,
+                    scratch,
+                    span,
+                }),
+            (
+                (register(), register(), register()),
+                any::<RegisterValue>(),
+                value(),
+                register(),
+                span
+            )
+                .prop_map(|((flag, d, e), e_start, b, scratch, span)| {
+                    Macro::ClearIfComposite {
+                        flag,
+                        d,
+                        e,
+                        e_start,
+                        b,
+                        scratch,
+                        span,
                     }
-                }
-            }
+                }),
+        ]
+    }
+
+    fn instruction() -> impl Strategy<Value = Instruction> {
+        use Instruction::*;
+        prop_oneof![
+            (register(), value()).prop_map(|(x, y)| Set(x, y)),
+            (register(), value()).prop_map(|(x, y)| Sub(x, y)),
+            (register(), value()).prop_map(|(x, y)| Mul(x, y)),
+            (value(), value()).prop_map(|(x, y)| Jnz(x, y)),
+            macro_op().prop_map(Instruction::Macro),
+        ]
+    }
+
+    #[test]
+    fn test_fork_from_snapshot() {
+        // `a` picks how many times to double `b`
+        let program =
+            Day23::parse("set b 1\nset c 3\njnz a 2\nset c 1\nmul b 2\nsub c 1\njnz c -2").unwrap();
+        let mut machine = Machine::new(&program, true);
+        machine.run_to(&[Breakpoint::At(2)]);
+        let checkpoint: Snapshot = machine.snapshot().to_string().parse().unwrap();
+
+        let fork = |a| {
+            let mut machine = Machine::new(&program, true);
+            machine.restore(&checkpoint);
+            machine.set_register('a', a);
+            machine.run();
+            machine.register('b')
         };
-        self.instruction_pointer = ip;
-        Ok(rv)
+        assert_eq!((fork(0), fork(1)), (2, 8));
     }
 
-    pub fn count_mul(&mut self) -> usize {
-        let mut invocations = 0;
-        while let Ok((instruction, _)) = self.execute() {
-            if let Instruction::Mul(_, _) = instruction {
-                invocations += 1;
-            }
-        }
-        invocations
+    #[test]
+    fn test_part2_needs_clean_halt() {
+        let program = Day23::parse("set h 3").unwrap();
+        assert_eq!(Day23::part2(&program), Some(3));
+        let program = Day23::parse("set h 3\nset b 9223372036854775807\nsub b -1").unwrap();
+        assert_eq!(Day23::part2(&program), None);
     }
 
-    pub fn run(&mut self) {
-        while let Ok(_) = self.execute() {}
+    #[test]
+    fn test_macro_span_must_advance() {
+        assert!(Day23::parse("mulsub a b c 3").is_ok());
+        assert!(Day23::parse("mulsub a b c 0").is_err());
+        assert!(Day23::parse("clrfactor f d e b g -1").is_err());
     }
 
-    pub fn registers(&self) -> &Registers {
-        &self.registers
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn test_round_trip(
+            instructions in prop::collection::vec(instruction(), 0..30),
+            labels in any::<bool>(),
+        ) {
+            let text = vm::disassemble(&instructions, labels);
+            prop_assert_eq!(Day23::parse(&text).unwrap(), instructions);
+        }
     }
 }
@@ -0,0 +1,234 @@
+//! A peephole optimizer which replaces recognized loops with macro-instructions
+//!
+//! Each recognized loop has its first instruction replaced by a `Macro` which
+//! summarizes the whole loop and then skips past it. Everything else is left
+//! in place, so relative jumps into or over the loop remain valid.
+
+use vm::Macro;
+
+use Instruction::{self, *};
+use RegisterID;
+use ValueHolder::{self, *};
+
+/// Find the loop beginning at the start of the code, if it's an idiom this recognizes
+type Recognizer = fn(&[Instruction]) -> Option<Macro>;
+
+/// Whether the value may depend on any of the given registers
+fn reads_any(value: ValueHolder, registers: &[RegisterID]) -> bool {
+    match value {
+        Register(id) => registers.contains(&id),
+        Number(_) => false,
+    }
+}
+
+/// Whether all the registers are distinct
+fn distinct(registers: &[RegisterID]) -> bool {
+    registers
+        .iter()
+        .enumerate()
+        .all(|(idx, id)| !registers[idx + 1..].contains(id))
+}
+
+/// `sub x y; sub n 1; jnz n -2`: multiplication by repeated subtraction
+fn mul_sub(code: &[Instruction]) -> Option<Macro> {
+    match *code.get(..3)? {
+        [Sub(x, y), Sub(counter, Number(1)), Jnz(Register(c), Number(-2))]
+            if c == counter && x != counter && !reads_any(y, &[x, counter]) =>
+        {
+            Some(Macro::MulSub {
+                x,
+                y,
+                counter,
+                span: 3,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The inner loop of a divisibility test:
+///
+/// ```text
+/// set g d
+/// mul g e
+/// sub g b
+/// jnz g 2
+/// set f 0
+/// sub e -1
+/// set g e
+/// sub g b
+/// jnz g -8
+/// ```
+fn clear_if_factor(code: &[Instruction]) -> Option<Macro> {
+    match *code.get(..9)? {
+        [Set(g, Register(d)), Mul(g1, Register(e)), Sub(g2, b), Jnz(Register(g3), Number(2)), Set(f, Number(0)), Sub(e1, Number(-1)), Set(g4, Register(e2)), Sub(g5, b1), Jnz(Register(g6), Number(-8))]
+            if [g1, g2, g3, g4, g5, g6].iter().all(|&id| id == g)
+                && e1 == e
+                && e2 == e
+                && b1 == b
+                && distinct(&[f, g, d, e])
+                && !reads_any(b, &[f, g, e]) =>
+        {
+            Some(Macro::ClearIfFactor {
+                flag: f,
+                d,
+                e,
+                b,
+                scratch: g,
+                span: 9,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// A divisibility test nested in a loop over every candidate divisor:
+///
+/// ```text
+/// set e 2
+/// <clear_if_factor>
+/// sub d -1
+/// set g d
+/// sub g b
+/// jnz g -13
+/// ```
+fn clear_if_composite(code: &[Instruction]) -> Option<Macro> {
+    let (e_start, e0) = match *code.first()? {
+        Set(e, Number(value)) => (value, e),
+        _ => return None,
+    };
+    let (flag, d, e, b, scratch) = match clear_if_factor(&code[1..])? {
+        Macro::ClearIfFactor {
+            flag,
+            d,
+            e,
+            b,
+            scratch,
+            ..
+        } => (flag, d, e, b, scratch),
+        _ => return None,
+    };
+    match *code.get(10..14)? {
+        [Sub(d1, Number(-1)), Set(g, Register(d2)), Sub(g1, b1), Jnz(Register(g2), Number(-13))]
+            if e0 == e
+                && d1 == d
+                && d2 == d
+                && g == scratch
+                && g1 == g
+                && g2 == g
+                && b1 == b
+                && !reads_any(b, &[d]) =>
+        {
+            Some(Macro::ClearIfComposite {
+                flag,
+                d,
+                e,
+                e_start,
+                b,
+                scratch,
+                span: 14,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Replace every recognized loop with an equivalent macro-instruction
+///
+/// Where loops are nested, the outermost recognized loop wins.
+pub fn optimize(instructions: &[Instruction]) -> Vec<Instruction> {
+    let recognizers: [Recognizer; 3] = [clear_if_composite, clear_if_factor, mul_sub];
+    (0..instructions.len())
+        .map(|idx| {
+            recognizers
+                .iter()
+                .find_map(|recognize| recognize(&instructions[idx..]))
+                .map(Instruction::Macro)
+                .unwrap_or(instructions[idx])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::Solution;
+    use {Day23, Machine};
+
+    /// The composite-counting program, with a range small enough to interpret
+    const PROGRAM: &str = "set b 57
+set c b
+jnz a 2
+jnz 1 5
+mul b 2
+sub b -3
+set c b
+sub c -68
+set f 1
+set d 2
+set e 2
+set g d
+mul g e
+sub g b
+jnz g 2
+set f 0
+sub e -1
+set g e
+sub g b
+jnz g -8
+sub d -1
+set g d
+sub g b
+jnz g -13
+jnz f 2
+sub h -1
+set g b
+sub g c
+jnz g 2
+jnz 1 3
+sub b -17
+jnz 1 -23";
+
+    #[test]
+    fn test_mul_sub() {
+        let program = Day23::parse("set c 5\nsub x -3\nsub c 1\njnz c -2").unwrap();
+        let optimized = optimize(&program);
+        assert_eq!(
+            optimized[1],
+            Instruction::Macro(mul_sub(&program[1..]).unwrap())
+        );
+        let mut machine = Machine::new(&optimized, true);
+        machine.run();
+        assert_eq!((machine.register('x'), machine.register('c')), (15, 0));
+    }
+
+    #[test]
+    fn test_recognizes_loops() {
+        let program = Day23::parse(PROGRAM).unwrap();
+        let optimized = optimize(&program);
+        let macros = optimized
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, instruction)| match *instruction {
+                Instruction::Macro(m) => Some((idx, m.span())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(macros, vec![(10, 14), (11, 9)]);
+    }
+
+    #[test]
+    fn test_optimized_equivalent() {
+        let program = Day23::parse(PROGRAM).unwrap();
+        let optimized = optimize(&program);
+        for &debug in &[true, false] {
+            let mut expect = Machine::new(&program, debug);
+            expect.run();
+            let mut actual = Machine::new(&optimized, debug);
+            actual.run();
+            for id in "abcdefgh".chars() {
+                assert_eq!(expect.register(id), actual.register(id), "register {}", id);
+            }
+        }
+    }
+}
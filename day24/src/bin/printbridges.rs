@@ -6,7 +6,7 @@ use util::file_as_by;
 
 fn main() {
     let input = file_as_by::<usize, _>("input.txt", |line| line.split("/").collect())
-        .expect("problem reading input");
+        .unwrap_or_else(|err| panic!("problem reading input: {}", err));
     let components = input.iter().map(|c| Component::new(c[0], c[1])).collect::<Vec<_>>();
 
     for bridge in BridgeBuilder::new(&components) {
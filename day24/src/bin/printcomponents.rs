@@ -1,17 +1,29 @@
 extern crate day24;
 use day24::{ComponentBin, Component};
 
+use std::fmt::Display;
+use std::fs::File;
+use std::io::BufReader;
+use std::process;
+
 extern crate util;
-use util::file_as_by;
+use util::stream::rows_as_by;
 
-fn main() {
-    let input = file_as_by::<usize, _>("input.txt", |line| line.split("/").collect())
-        .expect("problem reading input");
-    let components = input.iter().map(|c| Component::new(c[0], c[1])).collect::<Vec<_>>();
+/// Report a problem with the input, and exit
+fn fail<E: Display>(err: E) -> ! {
+    eprintln!("problem reading input.txt: {}", err);
+    process::exit(1);
+}
 
+fn main() {
+    let input = File::open("input.txt").unwrap_or_else(|err| fail(err));
     let mut bin = ComponentBin::new();
-    for component in components.iter() {
-        bin.insert(*component);
+    let mut rows = rows_as_by::<_, _, usize>(BufReader::new(input), |line| line.split('/').collect());
+    while let Some(row) = rows.next() {
+        match row.unwrap_or_else(|err| fail(err))[..] {
+            [left, right] => bin.insert(Component::new(left, right)),
+            _ => fail(rows.error_at_line("component must have 2 ports")),
+        }
     }
     for (key, cs) in bin.iter() {
         println!("{}:", key);
@@ -3,6 +3,10 @@ use std::collections::{BTreeSet, HashMap};
 use std::fmt;
 use std::ops::{Deref, DerefMut};
 
+extern crate util;
+use util::{Error, Solution};
+use util::stream::rows_as_by;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Component {
     left: usize,
@@ -27,8 +31,8 @@ impl PartialOrd for Component {
 impl Component {
     pub fn new(left: usize, right: usize) -> Component {
         Component {
-            left: left,
-            right: right,
+            left,
+            right,
         }
     }
 
@@ -53,6 +57,12 @@ pub struct ComponentBin {
     bin: HashMap<usize, BTreeSet<Component>>,
 }
 
+impl Default for ComponentBin {
+    fn default() -> ComponentBin {
+        ComponentBin::new()
+    }
+}
+
 impl ComponentBin {
     pub fn new() -> ComponentBin {
         ComponentBin {
@@ -63,31 +73,31 @@ impl ComponentBin {
     pub fn insert(&mut self, component: Component) {
         self.bin
             .entry(component.left)
-            .or_insert(BTreeSet::new())
+            .or_default()
             .insert(component);
         self.bin
             .entry(component.right)
-            .or_insert(BTreeSet::new())
+            .or_default()
             .insert(component);
     }
 
     fn remove(&mut self, component: Component) -> bool {
         let c_l = self.bin
             .entry(component.left)
-            .or_insert(BTreeSet::new())
+            .or_default()
             .remove(&component);
         let c_r = self.bin
             .entry(component.right)
-            .or_insert(BTreeSet::new())
+            .or_default()
             .remove(&component);
-        c_l && c_r || (c_l && component.left == component.right)
+        c_l && (c_r || component.left == component.right)
     }
 
     /// find the nth component, one of whose values is `side`, and
     /// remove and return the `index` biggest one.
     fn pop_nth(&mut self, side: usize, index: usize) -> Option<Component> {
         let nth_biggest = {
-            let set = self.bin.entry(side).or_insert(BTreeSet::new());
+            let set = self.bin.entry(side).or_default();
             set.iter().rev().skip(index).cloned().next()
         };
 
@@ -137,9 +147,8 @@ impl Bridge {
     }
 
     fn remove(&mut self) -> Option<Component> {
-        self.components.pop().map(|removed| {
+        self.components.pop().inspect(|removed| {
             self.end = removed.other(self.end).expect("how'd that get there?");
-            removed
         })
     }
 
@@ -147,6 +156,10 @@ impl Bridge {
         self.components.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
     pub fn strength(&self) -> usize {
         self.components
             .iter()
@@ -189,7 +202,7 @@ impl BridgeBuilder {
 impl Iterator for BridgeBuilder {
     type Item = Bridge;
     fn next(&mut self) -> Option<Bridge> {
-        if self.nstack.len() == 0 {
+        if self.nstack.is_empty() {
             return None;
         }
         // if we can add a component to the existing bridge, do that
@@ -218,7 +231,7 @@ impl Iterator for BridgeBuilder {
                 self.parts.insert(component);
 
                 if let Some(sl) = self.parts.size_len(self.bridge.end) {
-                    if self.nstack.len() > 0 && self.nstack[self.nstack.len() - 1] < sl {
+                    if !self.nstack.is_empty() && self.nstack[self.nstack.len() - 1] < sl {
                         success = true;
                         break;
                     }
@@ -243,3 +256,31 @@ pub fn max_length_strength_bridge(components: &[Component]) -> Option<Bridge> {
         otherwise => otherwise,
     })
 }
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input<'a> = Vec<Component>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Vec<Component>, Error> {
+        let mut rows = rows_as_by::<_, _, usize>(input.as_bytes(), |line| line.split('/').collect());
+        let mut components = Vec::new();
+        while let Some(row) = rows.next() {
+            match row?[..] {
+                [left, right] => components.push(Component::new(left, right)),
+                _ => return Err(rows.error_at_line("component must have 2 ports")),
+            }
+        }
+        Ok(components)
+    }
+
+    fn part1(components: &Vec<Component>) -> Option<usize> {
+        max_strength_bridge(components).map(|b| b.strength())
+    }
+
+    fn part2(components: &Vec<Component>) -> Option<usize> {
+        max_length_strength_bridge(components).map(|b| b.strength())
+    }
+}
@@ -1,8 +1,12 @@
 use std::collections::{HashMap, VecDeque};
 
-pub mod input;
+extern crate util;
+use util::{Error, Solution};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub mod parser;
+pub use parser::parse_blueprint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct Bit(bool);
 
 impl From<Bit> for usize {
@@ -14,12 +18,6 @@ impl From<Bit> for usize {
     }
 }
 
-impl Default for Bit {
-    fn default() -> Bit {
-        Bit(false)
-    }
-}
-
 pub type StateName = char;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -38,9 +36,9 @@ pub struct Instruction {
 impl Instruction {
     pub fn new(write: Bit, move_in: Direction, continue_with: StateName) -> Instruction {
         Instruction {
-            write: write,
-            move_in: move_in,
-            continue_with: continue_with,
+            write,
+            move_in,
+            continue_with,
         }
     }
 
@@ -56,6 +54,14 @@ impl Instruction {
 
 pub type States = HashMap<StateName, [Instruction; 2]>;
 
+/// A turing machine's states, along with how to run it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blueprint {
+    pub initial: StateName,
+    pub steps: usize,
+    pub states: States,
+}
+
 pub struct TuringMachine {
     tape: VecDeque<Bit>,
     cursor: usize,
@@ -69,10 +75,10 @@ impl TuringMachine {
         tape.push_back(Bit::default());
 
         TuringMachine {
-            tape: tape,
+            tape,
             cursor: 0,
             state: initial,
-            states: states,
+            states,
         }
     }
 
@@ -106,3 +112,105 @@ impl TuringMachine {
         self.tape.iter().map(|b| usize::from(*b)).sum()
     }
 }
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input<'a> = Blueprint;
+    type Answer1 = usize;
+    type Answer2 = &'static str;
+
+    fn parse(input: &str) -> Result<Blueprint, Error> {
+        parse_blueprint(input)
+            .map_err(|e| Error::parse(format!("line {}: expected {}", e.line, e.expected)))
+    }
+
+    fn part1(blueprint: &Blueprint) -> Option<usize> {
+        let mut machine = TuringMachine::new(blueprint.initial, blueprint.states.clone());
+        for _ in 0..blueprint.steps {
+            machine.execute();
+        }
+        Some(machine.checksum())
+    }
+
+    /// There is no second puzzle on the last day
+    fn part2(_: &Blueprint) -> Option<&'static str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser::ParseError;
+
+    const EXAMPLE: &str = "
+Begin in state A.
+Perform a diagnostic checksum after 6 steps.
+
+In state A:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state B.
+  If the current value is 1:
+    - Write the value 0.
+    - Move one slot to the left.
+    - Continue with state B.
+
+In state B:
+  If the current value is 0:
+    - Write the value 1.
+    - Move one slot to the left.
+    - Continue with state A.
+  If the current value is 1:
+    - Write the value 1.
+    - Move one slot to the right.
+    - Continue with state A.
+";
+
+    #[test]
+    fn test_example() {
+        let blueprint = Day25::parse(EXAMPLE).unwrap();
+        assert_eq!(blueprint.initial, 'A');
+        assert_eq!(blueprint.steps, 6);
+        assert_eq!(blueprint.states.len(), 2);
+        assert_eq!(Day25::part1(&blueprint), Some(3));
+    }
+
+    #[test]
+    fn test_bad_direction() {
+        let input = EXAMPLE.replacen("to the right", "up", 1);
+        assert_eq!(
+            parse_blueprint(&input),
+            Err(ParseError {
+                line: 8,
+                expected: "direction",
+            })
+        );
+    }
+
+    #[test]
+    fn test_undefined_next_state() {
+        let input = EXAMPLE.replacen("Continue with state A", "Continue with state C", 1);
+        assert_eq!(
+            parse_blueprint(&input),
+            Err(ParseError {
+                line: 19,
+                expected: "next state to be defined",
+            })
+        );
+    }
+
+    #[test]
+    fn test_duplicate_state() {
+        let input = EXAMPLE.replacen("In state B", "In state A", 1);
+        assert_eq!(
+            parse_blueprint(&input),
+            Err(ParseError {
+                line: 15,
+                expected: "each state to be defined once",
+            })
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Parse the blueprint for a turing machine
+//!
+//! The blueprint format is rigid enough that it's simplest to
+//! just walk through it line by line.
+
+use super::{Blueprint, Direction, Instruction, StateName, States};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-indexed line at which the problem occurred; 0 at the end of input
+    pub line: usize,
+    pub expected: &'static str,
+}
+
+/// The non-empty lines of the input, and the 1-indexed line number of each
+struct Lines<'a> {
+    lines: Vec<(usize, &'a str)>,
+    position: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(input: &'a str) -> Lines<'a> {
+        Lines {
+            lines: input
+                .lines()
+                .map(|line| line.trim())
+                .enumerate()
+                .filter(|&(_, line)| !line.is_empty())
+                .map(|(idx, line)| (idx + 1, line))
+                .collect(),
+            position: 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.lines.len()
+    }
+
+    /// The line number of the most recently consumed line
+    fn line(&self) -> usize {
+        match self.position {
+            0 => 0,
+            p => self.lines.get(p - 1).map_or(0, |&(line, _)| line),
+        }
+    }
+
+    /// Produce an error pointing at the most recently consumed line
+    fn error(&self, expected: &'static str) -> ParseError {
+        ParseError {
+            line: self.line(),
+            expected,
+        }
+    }
+
+    /// Consume the next line, returning its text between `prefix` and `suffix`
+    fn between(
+        &mut self,
+        prefix: &str,
+        suffix: &str,
+        expected: &'static str,
+    ) -> Result<&'a str, ParseError> {
+        let line = self.lines.get(self.position).map(|&(_, line)| line);
+        self.position += 1;
+        line.and_then(|line| line.strip_prefix(prefix))
+            .and_then(|rest| rest.strip_suffix(suffix))
+            .ok_or_else(|| self.error(expected))
+    }
+
+    fn state_name(&self, text: &str) -> Result<StateName, ParseError> {
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(name), None) => Ok(name),
+            _ => Err(self.error("single-character state name")),
+        }
+    }
+}
+
+fn parse_instruction(lines: &mut Lines, current: &str) -> Result<Instruction, ParseError> {
+    if lines.between("If the current value is ", ":", "current value")? != current {
+        return Err(lines.error("current values in order"));
+    }
+    let write = lines.between("- Write the value ", ".", "value to write")?;
+    let write = write.parse().map_err(|_| lines.error("0 or 1"))?;
+    let move_in = match lines.between("- Move one slot to the ", ".", "direction")? {
+        "left" => Direction::Left,
+        "right" => Direction::Right,
+        _ => return Err(lines.error("left or right")),
+    };
+    let continue_with = lines.between("- Continue with state ", ".", "next state")?;
+    let continue_with = lines.state_name(continue_with)?;
+    Instruction::new_i(write, move_in, continue_with).ok_or_else(|| lines.error("0 or 1"))
+}
+
+pub fn parse_blueprint(input: &str) -> Result<Blueprint, ParseError> {
+    let mut lines = Lines::new(input);
+
+    let initial = lines.between("Begin in state ", ".", "initial state")?;
+    let initial = lines.state_name(initial)?;
+    let steps = lines
+        .between(
+            "Perform a diagnostic checksum after ",
+            " steps.",
+            "checksum step count",
+        )?
+        .parse()
+        .map_err(|_| lines.error("number of steps"))?;
+
+    let mut states = States::new();
+    // each state continued with, and the line which names it
+    let mut targets = Vec::new();
+    while !lines.is_empty() {
+        let name = lines.between("In state ", ":", "state header")?;
+        let name = lines.state_name(name)?;
+        if states.contains_key(&name) {
+            return Err(lines.error("each state to be defined once"));
+        }
+        let on_zero = parse_instruction(&mut lines, "0")?;
+        targets.push((lines.line(), on_zero.continue_with));
+        let on_one = parse_instruction(&mut lines, "1")?;
+        targets.push((lines.line(), on_one.continue_with));
+        states.insert(name, [on_zero, on_one]);
+    }
+
+    if !states.contains_key(&initial) {
+        return Err(ParseError {
+            line: 1,
+            expected: "initial state to be defined",
+        });
+    }
+    if let Some(&(line, _)) = targets
+        .iter()
+        .find(|&&(_, target)| !states.contains_key(&target))
+    {
+        return Err(ParseError {
+            line,
+            expected: "next state to be defined",
+        });
+    }
+    Ok(Blueprint {
+        initial,
+        steps,
+        states,
+    })
+}
@@ -0,0 +1,106 @@
+//! Errors which can occur while reading and interpreting puzzle inputs
+
+use std::error;
+use std::fmt;
+use std::io;
+
+/// A location in the input text
+///
+/// Both fields are 1-indexed. The column counts characters, not bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+
+    /// Find the position of the given byte offset into `text`
+    ///
+    /// `offset` is clamped to the nearest character boundary at or before it.
+    pub fn at(text: &str, offset: usize) -> Position {
+        let mut offset = offset.min(text.len());
+        while !text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let preceding = &text[..offset];
+        let line_start = preceding.rfind('\n').map_or(0, |idx| idx + 1);
+        Position {
+            line: preceding.matches('\n').count() + 1,
+            column: preceding[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The input couldn't be read
+    Io(io::Error),
+    /// The input couldn't be split into tokens
+    Tokenize { position: Position, message: String },
+    /// A single token couldn't be parsed as the requested type
+    Token {
+        position: Position,
+        token: String,
+        message: String,
+    },
+    /// The input couldn't be parsed
+    Parse(String),
+}
+
+impl Error {
+    /// Construct a parse error from anything which can be displayed
+    pub fn parse<D: fmt::Display>(message: D) -> Error {
+        Error::Parse(message.to_string())
+    }
+
+    /// The position in the input at which this error occurred, if known
+    pub fn position(&self) -> Option<Position> {
+        match *self {
+            Error::Tokenize { position, .. } | Error::Token { position, .. } => Some(position),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "i/o error: {}", err),
+            Error::Tokenize {
+                position,
+                ref message,
+            } => write!(f, "{}: {}", position, message),
+            Error::Token {
+                position,
+                ref token,
+                ref message,
+            } => write!(f, "{}: couldn't parse {:?}: {}", position, token, message),
+            Error::Parse(ref message) => write!(f, "parse error: {}", message),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
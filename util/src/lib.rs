@@ -1,23 +1,39 @@
+use std::fmt::Display;
 use std::fs::File;
 use std::io::prelude::*;
 use std::str::FromStr;
 use std::mem;
 
+pub mod error;
 pub mod modular_math;
+pub mod solution;
+pub mod stream;
+
+pub use error::{Error, Position};
+pub use solution::Solution;
 
 pub type Tokenized<T> = Vec<Vec<T>>;
 pub type ParsedTokens<T> = Result<Tokenized<T>, <T as FromStr>::Err>;
 
 /// Read a file to a string
 ///
-/// Panic if anything goes wrong
-pub fn read_file(filename: &str) -> String {
-    let mut f = File::open(filename).expect("file not found");
-    let mut contents = String::new();
-    f.read_to_string(&mut contents).expect(
-        "Something went wrong reading the file",
-    );
-    contents
+/// If the file isn't valid UTF-8, the error points at the first invalid byte.
+pub fn read_file(filename: &str) -> Result<String, Error> {
+    let mut bytes = Vec::new();
+    File::open(filename)?.read_to_end(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|err| {
+        let valid_up_to = err.utf8_error().valid_up_to();
+        let valid = String::from_utf8_lossy(&err.as_bytes()[..valid_up_to]);
+        Error::Tokenize {
+            position: Position::at(&valid, valid_up_to),
+            message: "invalid UTF-8".to_string(),
+        }
+    })
+}
+
+/// Split a line into its whitespace-separated tokens
+fn whitespace(line: &str) -> Vec<&str> {
+    line.split_whitespace().collect()
 }
 
 /// Convert a string to a `Vec<Vec<&str>>`
@@ -26,10 +42,10 @@ pub fn read_file(filename: &str) -> String {
 /// The inner vector contains the tokens of the string, split by whitespace
 ///
 /// Empty lines and empty whitespace are discarded
-pub fn tokenize<'a>(input: &'a str) -> Tokenized<&'a str> {
+pub fn tokenize(input: &str) -> Tokenized<&str> {
     tokenize_by(input, |line| {
         line.split_whitespace()
-            .filter(|ref token| !token.is_empty())
+            .filter(|token| !token.is_empty())
             .collect::<Vec<_>>()
     })
 }
@@ -50,7 +66,7 @@ where
     input
         .lines()
         .map(line_tokenizer)
-        .filter(|ref line| !line.is_empty())
+        .filter(|line| !line.is_empty())
         .collect()
 }
 
@@ -74,12 +90,80 @@ where
         .collect()
 }
 
+/// Byte offset of `token` within `line`
+///
+/// Tokens are normally subslices of their line; otherwise, fall back to searching for them.
+fn offset_in(line: &str, token: &str) -> usize {
+    let line_start = line.as_ptr() as usize;
+    let token_start = token.as_ptr() as usize;
+    if token_start >= line_start && token_start + token.len() <= line_start + line.len() {
+        token_start - line_start
+    } else {
+        line.find(token).unwrap_or(0)
+    }
+}
+
+/// Parse each token of a single line, reporting the position of the first failure
+fn parse_row<Output>(line_number: usize, line: &str, tokens: Vec<&str>) -> Result<Vec<Output>, Error>
+where
+    Output: FromStr,
+    Output::Err: Display,
+{
+    tokens
+        .into_iter()
+        .map(|token| {
+            token.parse::<Output>().map_err(|err| Error::Token {
+                position: Position::new(
+                    line_number,
+                    Position::at(line, offset_in(line, token)).column,
+                ),
+                token: token.to_string(),
+                message: err.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Tokenize a string and parse the tokens as the specified type
+///
+/// Unlike `parse_as`, a failure reports the line and column of the offending token.
+pub fn str_as<Output>(input: &str) -> Result<Tokenized<Output>, Error>
+where
+    Output: FromStr,
+    Output::Err: Display,
+{
+    str_as_by(input, whitespace)
+}
+
+/// Tokenize a string by the given line tokenization function,
+/// and parse the tokens as the specified type
+///
+/// Empty lines are discarded. A failure reports the line and column of the offending token.
+pub fn str_as_by<'a, Output, LineTokenizer>(
+    input: &'a str,
+    line_tokenizer: LineTokenizer,
+) -> Result<Tokenized<Output>, Error>
+where
+    LineTokenizer: Fn(&'a str) -> Vec<&'a str>,
+    Output: FromStr,
+    Output::Err: Display,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx, line, line_tokenizer(line)))
+        .filter(|(_, _, tokens)| !tokens.is_empty())
+        .map(|(idx, line, tokens)| parse_row(idx + 1, line, tokens))
+        .collect()
+}
+
 /// Read the file whose path is given, tokenize it, and parse the tokens as the specified type
-pub fn file_as<Output>(filename: &str) -> ParsedTokens<Output>
+pub fn file_as<Output>(filename: &str) -> Result<Tokenized<Output>, Error>
 where
     Output: FromStr,
+    Output::Err: Display,
 {
-    parse_as(&tokenize(&read_file(filename)))
+    str_as(&read_file(filename)?)
 }
 
 /// Read the file whose path is given, tokenize it by the given line tokenization function,
@@ -87,12 +171,13 @@ where
 pub fn file_as_by<Output, LineTokenizer>(
     filename: &str,
     line_tokenizer: LineTokenizer,
-) -> ParsedTokens<Output>
+) -> Result<Tokenized<Output>, Error>
 where
     LineTokenizer: Fn(&str) -> Vec<&str>,
     Output: FromStr,
+    Output::Err: Display,
 {
-    parse_as(&tokenize_by(&read_file(filename), line_tokenizer))
+    str_as_by(&read_file(filename)?, line_tokenizer)
 }
 
 /// Convert a `Vec<Vec<T>>` -> `Vec<T>`
@@ -103,7 +188,7 @@ where
 pub fn flatten<T: Default>(input: Tokenized<T>) -> Vec<T> {
     input
         .into_iter()
-        .filter(|sl| sl.len() > 0)
+        .filter(|sl| !sl.is_empty())
         .map(|mut sl| {
             let mut val = T::default();
             mem::swap(&mut sl[0], &mut val);
@@ -123,3 +208,41 @@ macro_rules! debug_println {
         println!($($item),+);
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str_as() {
+        assert_eq!(
+            str_as::<u32>("1 2\n\n3").unwrap(),
+            vec![vec![1, 2], vec![3]]
+        );
+    }
+
+    #[test]
+    fn test_str_as_token_position() {
+        let err = str_as::<u32>("1 2\n\n3  x4 5").unwrap_err();
+        assert_eq!(err.position(), Some(Position::new(3, 4)));
+        match err {
+            Error::Token { token, .. } => assert_eq!(token, "x4"),
+            _ => panic!("expected a token error, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_str_as_by_token_position() {
+        let err = str_as_by::<usize, _>("0/2\n2/ä", |line| line.split('/').collect())
+            .unwrap_err();
+        assert_eq!(err.position(), Some(Position::new(2, 3)));
+    }
+
+    #[test]
+    fn test_position_at() {
+        assert_eq!(Position::at("ab\ncä\nd", 0), Position::new(1, 1));
+        assert_eq!(Position::at("ab\ncä\nd", 3), Position::new(2, 1));
+        assert_eq!(Position::at("ab\ncä\nd", 6), Position::new(2, 3));
+        assert_eq!(Position::at("ab\ncä\nd", 7), Position::new(3, 1));
+    }
+}
@@ -75,7 +75,7 @@ mod tests {
     #[test]
     fn test_mul_mod() {
         let half = 1 << 16;
-        let max = ::std::u64::MAX;
+        let max = u64::MAX;
 
         assert_eq!(mul_mod(0, 0, 2), 0);
         assert_eq!(mul_mod(1, 0, 2), 0);
@@ -0,0 +1,27 @@
+//! A common interface to every day's puzzle
+//!
+//! Each day crate exposes a unit struct which implements `Solution`.
+//! That lets tooling such as the runner treat every puzzle the same way:
+//! parse the input once, then compute each part from the parsed input.
+
+use std::fmt::Display;
+
+use error::Error;
+
+pub trait Solution {
+    /// The parsed form of the puzzle input
+    ///
+    /// This may borrow from the input text.
+    type Input<'a>;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    /// Interpret the raw puzzle input
+    fn parse(input: &str) -> Result<Self::Input<'_>, Error>;
+
+    /// Solve part 1, or `None` if the input has no solution
+    fn part1(input: &Self::Input<'_>) -> Option<Self::Answer1>;
+
+    /// Solve part 2, or `None` if the input has no solution
+    fn part2(input: &Self::Input<'_>) -> Option<Self::Answer2>;
+}
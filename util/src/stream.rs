@@ -0,0 +1,173 @@
+//! Streaming equivalents of `tokenize`/`parse_as`
+//!
+//! `file_as` and friends read the whole input, then build a `Tokenized<T>`
+//! before anything is consumed. The iterators here instead read, tokenize,
+//! and parse one line at a time from any `BufRead`, so only a single line
+//! is held in memory at once.
+//!
+//! A `&str` can be streamed with `rows_as(input.as_bytes())`.
+
+use std::fmt::Display;
+use std::io::BufRead;
+use std::marker::PhantomData;
+use std::str::{self, FromStr};
+
+use error::{Error, Position};
+
+/// The whitespace tokenizer used by `rows_as`
+pub type Whitespace = fn(&str) -> Vec<&str>;
+
+/// An iterator over the parsed rows of a `BufRead`
+///
+/// Empty rows are skipped. After the first error, the iterator is fused.
+pub struct Rows<R, LineTokenizer, Output> {
+    reader: R,
+    line_tokenizer: LineTokenizer,
+    buffer: Vec<u8>,
+    line_number: usize,
+    done: bool,
+    output: PhantomData<Output>,
+}
+
+impl<R, LineTokenizer, Output> Rows<R, LineTokenizer, Output>
+where
+    R: BufRead,
+    LineTokenizer: Fn(&str) -> Vec<&str>,
+    Output: FromStr,
+    Output::Err: Display,
+{
+    pub fn new(reader: R, line_tokenizer: LineTokenizer) -> Rows<R, LineTokenizer, Output> {
+        Rows {
+            reader,
+            line_tokenizer,
+            buffer: Vec::new(),
+            line_number: 0,
+            done: false,
+            output: PhantomData,
+        }
+    }
+
+    /// The 1-indexed number of the line most recently read
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Construct a tokenization error pointing at the start of the line most recently read
+    ///
+    /// Useful when a row parsed correctly but doesn't have the expected shape.
+    pub fn error_at_line<D: Display>(&self, message: D) -> Error {
+        Error::Tokenize {
+            position: Position::new(self.line_number, 1),
+            message: message.to_string(),
+        }
+    }
+
+    /// Read the next line into the buffer, returning `false` at the end of input
+    fn read_line(&mut self) -> Result<bool, Error> {
+        self.buffer.clear();
+        if self.reader.read_until(b'\n', &mut self.buffer)? == 0 {
+            return Ok(false);
+        }
+        self.line_number += 1;
+        while self.buffer.last() == Some(&b'\n') || self.buffer.last() == Some(&b'\r') {
+            self.buffer.pop();
+        }
+        Ok(true)
+    }
+
+    fn next_row(&mut self) -> Result<Option<Vec<Output>>, Error> {
+        while self.read_line()? {
+            let line = str::from_utf8(&self.buffer).map_err(|err| {
+                let valid = String::from_utf8_lossy(&self.buffer[..err.valid_up_to()]);
+                Error::Tokenize {
+                    position: Position::new(
+                        self.line_number,
+                        Position::at(&valid, err.valid_up_to()).column,
+                    ),
+                    message: "invalid UTF-8".to_string(),
+                }
+            })?;
+            let tokens = (self.line_tokenizer)(line);
+            if !tokens.is_empty() {
+                return super::parse_row(self.line_number, line, tokens).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<R, LineTokenizer, Output> Iterator for Rows<R, LineTokenizer, Output>
+where
+    R: BufRead,
+    LineTokenizer: Fn(&str) -> Vec<&str>,
+    Output: FromStr,
+    Output::Err: Display,
+{
+    type Item = Result<Vec<Output>, Error>;
+
+    fn next(&mut self) -> Option<Result<Vec<Output>, Error>> {
+        if self.done {
+            return None;
+        }
+        let row = self.next_row();
+        if let Ok(None) | Err(_) = row {
+            self.done = true;
+        }
+        row.transpose()
+    }
+}
+
+/// Lazily parse each whitespace-separated token of the reader as the specified type
+pub fn rows_as<R, Output>(reader: R) -> Rows<R, Whitespace, Output>
+where
+    R: BufRead,
+    Output: FromStr,
+    Output::Err: Display,
+{
+    Rows::new(reader, super::whitespace)
+}
+
+/// Lazily tokenize the reader by the given line tokenization function,
+/// and parse each token as the specified type
+pub fn rows_as_by<R, LineTokenizer, Output>(
+    reader: R,
+    line_tokenizer: LineTokenizer,
+) -> Rows<R, LineTokenizer, Output>
+where
+    R: BufRead,
+    LineTokenizer: Fn(&str) -> Vec<&str>,
+    Output: FromStr,
+    Output::Err: Display,
+{
+    Rows::new(reader, line_tokenizer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_as() {
+        let rows = rows_as::<_, u32>("1 2\r\n\n3\n".as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(rows, vec![vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_rows_as_by_stops_at_error() {
+        let mut rows = rows_as_by::<_, _, usize>("0/2\n2/x\n3/4".as_bytes(), |line| {
+            line.split('/').collect()
+        });
+        assert_eq!(rows.next().unwrap().unwrap(), vec![0, 2]);
+        let err = rows.next().unwrap().unwrap_err();
+        assert_eq!(err.position(), Some(Position::new(2, 3)));
+        assert!(rows.next().is_none());
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let err = rows_as::<_, u32>(&b"1\n2 \xff"[..]).nth(1).unwrap().unwrap_err();
+        assert_eq!(err.position(), Some(Position::new(2, 3)));
+    }
+}
@@ -0,0 +1,64 @@
+//! What happens when a result doesn't fit in a register
+//!
+//! Every operation is computed exactly, at double width, and then narrowed
+//! back to a register value according to the machine's `Arithmetic` policy.
+
+use std::convert::TryFrom;
+
+use RegisterValue;
+
+/// How to handle results which overflow a register
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Arithmetic {
+    /// Wrap around in two's complement, as a release build of plain `i64` operators does
+    Wrapping,
+    /// Stop the machine with `MachineError::Overflow`
+    #[default]
+    Checked,
+    /// Clamp to the nearest representable value
+    Saturating,
+}
+
+/// The exact result of an operation, before it's narrowed to fit a register
+pub(crate) type Wide = i128;
+
+impl Arithmetic {
+    /// Narrow an exact result to a register value, or `None` if it overflows a checked register
+    pub(crate) fn narrow(self, exact: Wide) -> Option<RegisterValue> {
+        match self {
+            Arithmetic::Wrapping => Some(exact as RegisterValue),
+            Arithmetic::Checked => RegisterValue::try_from(exact).ok(),
+            Arithmetic::Saturating => Some(
+                exact.clamp(RegisterValue::MIN as Wide, RegisterValue::MAX as Wide)
+                    as RegisterValue,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_narrow() {
+        let big = RegisterValue::MAX as Wide + 2;
+        assert_eq!(
+            Arithmetic::Wrapping.narrow(big),
+            Some(RegisterValue::MIN + 1)
+        );
+        assert_eq!(Arithmetic::Checked.narrow(big), None);
+        assert_eq!(Arithmetic::Saturating.narrow(big), Some(RegisterValue::MAX));
+        assert_eq!(
+            Arithmetic::Saturating.narrow(-big),
+            Some(RegisterValue::MIN)
+        );
+        for policy in &[
+            Arithmetic::Wrapping,
+            Arithmetic::Checked,
+            Arithmetic::Saturating,
+        ] {
+            assert_eq!(policy.narrow(-5), Some(-5));
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Canonical assembly text for instructions, and labels for jump targets
+//!
+//! Every instruction set's mnemonics are those of the core `Op`s, so any
+//! instruction prints as its `Op`. `disassemble` can optionally replace the
+//! constant offsets of jumps with labels; `resolve_labels` turns such text back
+//! into plain offsets, which `parse_lines` then hands to each puzzle's parser one
+//! line at a time.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use {Instruction, Op, ValueHolder};
+
+impl fmt::Display for ValueHolder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ValueHolder::Register(id) => write!(f, "{}", id),
+            ValueHolder::Number(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Op {
+    /// The name of this operation in assembly text
+    pub fn mnemonic(&self) -> &'static str {
+        use Op::*;
+        match *self {
+            Set(..) => "set",
+            Add(..) => "add",
+            Sub(..) => "sub",
+            Mul(..) => "mul",
+            Mod(..) => "mod",
+            Snd(..) => "snd",
+            Rcv(..) => "rcv",
+            Jgz(..) => "jgz",
+            Jnz(..) => "jnz",
+            Macro(m) => m.mnemonic(),
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Op::*;
+        match *self {
+            Set(x, y) | Add(x, y) | Sub(x, y) | Mul(x, y) | Mod(x, y) => {
+                write!(f, "{} {} {}", self.mnemonic(), x, y)
+            }
+            Snd(x) => write!(f, "{} {}", self.mnemonic(), x),
+            Rcv(x) => write!(f, "{} {}", self.mnemonic(), x),
+            Jgz(x, y) | Jnz(x, y) => write!(f, "{} {} {}", self.mnemonic(), x, y),
+            Macro(m) => write!(f, "{}", m),
+        }
+    }
+}
+
+/// The index a jump instruction at `ip` targets, if its offset is a constant
+fn jump_target(ip: usize, op: Op) -> Option<i64> {
+    match op {
+        Op::Jgz(_, ValueHolder::Number(offset)) | Op::Jnz(_, ValueHolder::Number(offset)) => {
+            (ip as i64).checked_add(offset)
+        }
+        _ => None,
+    }
+}
+
+/// Print a program in canonical source form, one instruction per line
+///
+/// With `labels`, every jump by a constant offset which lands inside the program
+/// (or just past its end) jumps to a label instead. Labels are named `L0`, `L1`, …
+/// in program order, and each is defined on its own line before its target.
+pub fn disassemble<I: Instruction>(instructions: &[I], labels: bool) -> String {
+    let mut names = HashMap::new();
+    if labels {
+        let mut targets = instructions
+            .iter()
+            .enumerate()
+            .filter_map(|(ip, instruction)| jump_target(ip, instruction.op()))
+            .filter(|&target| target >= 0 && target as usize <= instructions.len())
+            .map(|target| target as usize)
+            .collect::<Vec<_>>();
+        targets.sort();
+        targets.dedup();
+        for (idx, target) in targets.into_iter().enumerate() {
+            names.insert(target, format!("L{}", idx));
+        }
+    }
+
+    let mut out = String::new();
+    for (ip, instruction) in instructions.iter().enumerate() {
+        if let Some(name) = names.get(&ip) {
+            out.push_str(&format!("{}:\n", name));
+        }
+        let op = instruction.op();
+        let label = jump_target(ip, op)
+            .filter(|&target| target >= 0)
+            .and_then(|target| names.get(&(target as usize)));
+        match (op, label) {
+            (Op::Jgz(x, _), Some(name)) | (Op::Jnz(x, _), Some(name)) => {
+                out.push_str(&format!("{} {} {}\n", op.mnemonic(), x, name))
+            }
+            _ => out.push_str(&format!("{}\n", op)),
+        }
+    }
+    if let Some(name) = names.get(&instructions.len()) {
+        out.push_str(&format!("{}:\n", name));
+    }
+    out
+}
+
+/// Why assembly text couldn't have its labels resolved, or couldn't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelError {
+    /// 1-indexed
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LabelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Whether a token can name a label: registers are a single letter, so labels are longer
+fn is_label(token: &str) -> bool {
+    token.len() > 1
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !token.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Replace jumps to labels with relative offsets, and label definitions with blank lines
+///
+/// A label is defined by a line containing only `name:`, and refers to the next
+/// instruction. Only the offset of a `jgz` or `jnz` may be a label. Every line keeps
+/// its line number, so later parse errors still point at the right place.
+pub fn resolve_labels(source: &str) -> Result<String, LabelError> {
+    let mut labels = HashMap::new();
+    let mut ip = 0;
+    for (idx, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match line.strip_suffix(':') {
+            Some(name) if is_label(name) => {
+                if labels.insert(name, ip).is_some() {
+                    return Err(LabelError {
+                        line: idx + 1,
+                        message: format!("label {} defined twice", name),
+                    });
+                }
+            }
+            _ => ip += 1,
+        }
+    }
+
+    let mut out = String::with_capacity(source.len());
+    let mut ip = 0;
+    for (idx, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.strip_suffix(':').is_some_and(is_label) {
+            out.push('\n');
+            continue;
+        }
+        let mut tokens = trimmed.split_whitespace().collect::<Vec<_>>();
+        let is_jump = tokens.len() == 3 && (tokens[0] == "jgz" || tokens[0] == "jnz");
+        if is_jump && is_label(tokens[2]) {
+            let target = labels.get(tokens[2]).ok_or_else(|| LabelError {
+                line: idx + 1,
+                message: format!("undefined label {}", tokens[2]),
+            })?;
+            let offset = (*target as i64 - ip as i64).to_string();
+            tokens[2] = &offset;
+            out.push_str(&tokens.join(" "));
+        } else {
+            out.push_str(line);
+        }
+        out.push('\n');
+        ip += 1;
+    }
+    Ok(out)
+}
+
+/// A parser for a single line of assembly, such as one generated by LALRPOP
+///
+/// Implemented for every function from a line to a result, even when its error
+/// borrows from the line.
+pub trait ParseLine<'a, I> {
+    type Error: fmt::Debug;
+
+    fn parse_line(&self, line: &'a str) -> Result<I, Self::Error>;
+}
+
+impl<'a, I, E, F> ParseLine<'a, I> for F
+where
+    F: Fn(&'a str) -> Result<I, E>,
+    E: fmt::Debug,
+{
+    type Error = E;
+
+    fn parse_line(&self, line: &'a str) -> Result<I, E> {
+        self(line)
+    }
+}
+
+/// Resolve a program's labels, then parse each of its non-blank lines
+///
+/// An error names the 1-indexed line on which it was found.
+pub fn parse_lines<I, P>(source: &str, parser: P) -> Result<Vec<I>, LabelError>
+where
+    P: for<'a> ParseLine<'a, I>,
+{
+    resolve_labels(source)?
+        .lines()
+        .enumerate()
+        .filter(|&(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            parser.parse_line(line.trim()).map_err(|e| LabelError {
+                line: idx + 1,
+                message: format!("{:?}", e),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Op::*;
+    use ValueHolder::*;
+
+    const PROGRAM: [Op; 4] = [
+        Set('a', Number(2)),
+        Add('b', Number(-3)),
+        Sub('a', Number(1)),
+        Jnz(Register('a'), Number(-2)),
+    ];
+
+    #[test]
+    fn test_disassemble() {
+        assert_eq!(
+            disassemble(&PROGRAM, false),
+            "set a 2\nadd b -3\nsub a 1\njnz a -2\n"
+        );
+    }
+
+    #[test]
+    fn test_disassemble_labels() {
+        let program = [Jgz(Number(1), Number(2)), Jnz(Register('a'), Number(-1))];
+        assert_eq!(
+            disassemble(&program, true),
+            "L0:\njgz 1 L1\njnz a L0\nL1:\n"
+        );
+    }
+
+    #[test]
+    fn test_resolve_labels() {
+        let text = disassemble(&PROGRAM, true);
+        assert_eq!(text, "set a 2\nL0:\nadd b -3\nsub a 1\njnz a L0\n");
+        assert_eq!(
+            resolve_labels(&text).unwrap(),
+            "set a 2\n\nadd b -3\nsub a 1\njnz a -2\n"
+        );
+    }
+
+    #[test]
+    fn test_undefined_label() {
+        assert_eq!(
+            resolve_labels("set a 1\njnz a end"),
+            Err(LabelError {
+                line: 2,
+                message: "undefined label end".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let parse = |line: &str| line.parse::<i64>();
+        assert_eq!(parse_lines("1\n\nL0:\n -2 \n", parse), Ok(vec![1, -2]));
+        assert_eq!(parse_lines("1\n\nx", parse).unwrap_err().line, 3);
+    }
+}
@@ -0,0 +1,118 @@
+//! The form in which a `Machine` actually executes its program
+//!
+//! Compilation lowers each instruction to its `Op`, resolves register names to
+//! slots in the register array, and turns jumps by constant offsets into jumps
+//! to absolute targets. Executing bytecode never needs to look anything up.
+
+use {Instruction, Macro, Op, RegisterValue, Registers, ValueHolder};
+
+/// A resolved `ValueHolder`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Slot(usize),
+    Immediate(RegisterValue),
+}
+
+impl Operand {
+    fn compile(value: ValueHolder) -> Operand {
+        match value {
+            ValueHolder::Register(id) => Operand::Slot(Registers::slot(id)),
+            ValueHolder::Number(value) => Operand::Immediate(value),
+        }
+    }
+
+    #[inline]
+    pub fn value(&self, registers: &Registers) -> RegisterValue {
+        match *self {
+            Operand::Slot(slot) => registers[slot],
+            Operand::Immediate(value) => value,
+        }
+    }
+}
+
+/// Where a jump goes if it is taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// The instruction pointer to jump to
+    Absolute(RegisterValue),
+    /// An offset only known at runtime
+    Relative(Operand),
+}
+
+impl Target {
+    fn compile(ip: usize, offset: ValueHolder) -> Target {
+        match offset {
+            ValueHolder::Number(offset) => match (ip as RegisterValue).checked_add(offset) {
+                Some(target) => Target::Absolute(target),
+                None => Target::Relative(Operand::Immediate(offset)),
+            },
+            register => Target::Relative(Operand::compile(register)),
+        }
+    }
+}
+
+/// A single compiled instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Code {
+    Set(usize, Operand),
+    Add(usize, Operand),
+    Sub(usize, Operand),
+    Mul(usize, Operand),
+    Mod(usize, Operand),
+    Snd(Operand),
+    Rcv(usize),
+    Jgz(Operand, Target),
+    Jnz(Operand, Target),
+    Macro(Macro),
+}
+
+/// Compile an instruction found at the given index
+///
+/// Panics if the instruction names a register outside `a..=z`.
+pub fn compile_one<I: Instruction>(ip: usize, instruction: &I) -> Code {
+    use self::Operand as O;
+    match instruction.op() {
+        Op::Set(x, y) => Code::Set(Registers::slot(x), O::compile(y)),
+        Op::Add(x, y) => Code::Add(Registers::slot(x), O::compile(y)),
+        Op::Sub(x, y) => Code::Sub(Registers::slot(x), O::compile(y)),
+        Op::Mul(x, y) => Code::Mul(Registers::slot(x), O::compile(y)),
+        Op::Mod(x, y) => Code::Mod(Registers::slot(x), O::compile(y)),
+        Op::Snd(x) => Code::Snd(O::compile(x)),
+        Op::Rcv(x) => Code::Rcv(Registers::slot(x)),
+        Op::Jgz(x, y) => Code::Jgz(O::compile(x), Target::compile(ip, y)),
+        Op::Jnz(x, y) => Code::Jnz(O::compile(x), Target::compile(ip, y)),
+        Op::Macro(m) => Code::Macro(m),
+    }
+}
+
+/// Compile a whole program
+pub fn compile<I: Instruction>(instructions: &[I]) -> Vec<Code> {
+    instructions
+        .iter()
+        .enumerate()
+        .map(|(ip, instruction)| compile_one(ip, instruction))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ValueHolder::*;
+
+    #[test]
+    fn test_compile() {
+        let program = [
+            Op::Set('b', Number(3)),
+            Op::Jnz(Register('a'), Number(-1)),
+            Op::Jgz(Number(1), Register('z')),
+        ];
+        assert_eq!(
+            compile(&program),
+            vec![
+                Code::Set(1, Operand::Immediate(3)),
+                Code::Jnz(Operand::Slot(0), Target::Absolute(0)),
+                Code::Jgz(Operand::Immediate(1), Target::Relative(Operand::Slot(25))),
+            ]
+        );
+    }
+}
@@ -0,0 +1,360 @@
+//! Io over `mpsc` channels, for machines each running on their own thread
+//!
+//! Every machine's `Io` shares a `Coordinator`, which knows how many messages are
+//! in flight to each machine and which machines are waiting or halted. When every
+//! machine is either halted or waiting with nothing in flight to it, no machine
+//! can ever make progress again: the coordinator declares a deadlock, and every
+//! waiting `receive` returns `None`, so each machine stops with `ReadBlock`.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+
+use {Instruction, Io, Machine, MachineError, RegisterValue};
+
+struct State {
+    /// Messages sent to each machine and not yet received
+    pending: Vec<usize>,
+    halted: Vec<bool>,
+    waiting: usize,
+    deadlocked: bool,
+}
+
+impl State {
+    /// Whether no machine can ever make progress again
+    fn is_stuck(&self) -> bool {
+        let halted = self.halted.iter().filter(|&&halted| halted).count();
+        self.waiting + halted == self.halted.len()
+            && self
+                .pending
+                .iter()
+                .zip(&self.halted)
+                .all(|(&pending, &halted)| halted || pending == 0)
+    }
+}
+
+/// Tracks the machines of a network to detect global deadlock
+pub struct Coordinator {
+    state: Mutex<State>,
+    wake: Condvar,
+}
+
+impl Coordinator {
+    fn new(machines: usize) -> Coordinator {
+        Coordinator {
+            state: Mutex::new(State {
+                pending: vec![0; machines],
+                halted: vec![false; machines],
+                waiting: 0,
+                deadlocked: false,
+            }),
+            wake: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("coordinator lock poisoned")
+    }
+
+    /// Whether every machine which hasn't halted was found blocked on an empty queue
+    pub fn is_deadlocked(&self) -> bool {
+        self.lock().deadlocked
+    }
+}
+
+/// Io for one machine of a network connected by channels
+///
+/// Each message sent goes to every machine this one has an edge to.
+pub struct ChannelIo {
+    id: usize,
+    coordinator: Arc<Coordinator>,
+    outputs: Vec<(usize, Sender<RegisterValue>)>,
+    input: Receiver<RegisterValue>,
+    /// Messages taken off `input` to be inspected, which are still to be received
+    buffer: RefCell<VecDeque<RegisterValue>>,
+    sent: usize,
+    received: usize,
+}
+
+impl ChannelIo {
+    /// Connect a network of machines
+    ///
+    /// `edges[i]` lists the machines to which machine `i` sends its messages.
+    /// Returns the io for each machine, in order.
+    pub fn network(edges: &[Vec<usize>]) -> Vec<ChannelIo> {
+        let coordinator = Arc::new(Coordinator::new(edges.len()));
+        let (senders, receivers): (Vec<_>, Vec<_>) = edges.iter().map(|_| channel()).unzip();
+        receivers
+            .into_iter()
+            .zip(edges)
+            .enumerate()
+            .map(|(id, (input, targets))| ChannelIo {
+                id,
+                coordinator: coordinator.clone(),
+                outputs: targets
+                    .iter()
+                    .map(|&target| (target, senders[target].clone()))
+                    .collect(),
+                input,
+                buffer: RefCell::new(VecDeque::new()),
+                sent: 0,
+                received: 0,
+            })
+            .collect()
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn coordinator(&self) -> &Arc<Coordinator> {
+        &self.coordinator
+    }
+
+    /// How many messages this machine has sent, counting each recipient separately
+    pub fn sent(&self) -> usize {
+        self.sent
+    }
+
+    pub fn received(&self) -> usize {
+        self.received
+    }
+}
+
+impl Io for ChannelIo {
+    fn send(&mut self, message: RegisterValue) {
+        let mut state = self.coordinator.lock();
+        for &(target, ref sender) in &self.outputs {
+            // a halted machine has dropped its receiver; its messages are lost
+            if sender.send(message).is_ok() {
+                state.pending[target] += 1;
+            }
+            self.sent += 1;
+        }
+        self.coordinator.wake.notify_all();
+    }
+
+    fn receive(&mut self) -> Option<RegisterValue> {
+        let mut state = self.coordinator.lock();
+        loop {
+            if state.pending[self.id] > 0 {
+                state.pending[self.id] -= 1;
+                drop(state);
+                let buffered = self.buffer.borrow_mut().pop_front();
+                let message = match buffered {
+                    Some(message) => message,
+                    None => self.input.recv().ok()?,
+                };
+                self.received += 1;
+                return Some(message);
+            }
+            if state.deadlocked {
+                return None;
+            }
+            state.waiting += 1;
+            if state.is_stuck() {
+                state.deadlocked = true;
+                state.waiting -= 1;
+                self.coordinator.wake.notify_all();
+                return None;
+            }
+            state = self
+                .coordinator
+                .wake
+                .wait(state)
+                .expect("coordinator lock poisoned");
+            state.waiting -= 1;
+        }
+    }
+
+    fn inbox(&self) -> Vec<RegisterValue> {
+        // holding the lock, everything counted as pending is already in the channel
+        let _state = self.coordinator.lock();
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.extend(self.input.try_iter());
+        buffer.iter().cloned().collect()
+    }
+
+    fn restore_inbox(&mut self, messages: &[RegisterValue]) {
+        let mut state = self.coordinator.lock();
+        let buffer = self.buffer.get_mut();
+        // the old inbox is replaced, not received, so it no longer counts as pending
+        self.input.try_iter().for_each(drop);
+        buffer.clear();
+        buffer.extend(messages);
+        state.pending[self.id] = buffer.len();
+        self.coordinator.wake.notify_all();
+    }
+}
+
+impl Drop for ChannelIo {
+    /// Machines waiting on this one may now be deadlocked
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.coordinator.state.lock() {
+            state.halted[self.id] = true;
+        }
+        self.coordinator.wake.notify_all();
+    }
+}
+
+/// How a single machine of a network finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Report {
+    pub sent: usize,
+    pub received: usize,
+    /// `ReadBlock` if the machine was deadlocked, `IPOutOfBounds` if it halted
+    pub stopped: MachineError,
+}
+
+/// How a whole network finished
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    pub machines: Vec<Report>,
+    pub deadlocked: bool,
+}
+
+impl Summary {
+    /// Whether every machine halted on its own, rather than by deadlock
+    pub fn terminated(&self) -> bool {
+        !self.deadlocked
+    }
+
+    /// The machines which were left blocked on empty queues
+    pub fn deadlocked_machines(&self) -> Vec<usize> {
+        self.machines
+            .iter()
+            .enumerate()
+            .filter(|&(_, report)| report.stopped == MachineError::ReadBlock)
+            .map(|(id, _)| id)
+            .collect()
+    }
+}
+
+/// Run each machine on its own thread until every one has halted or deadlocked
+pub fn run<I>(machines: Vec<Machine<I, ChannelIo>>) -> Summary
+where
+    I: Instruction + Sync,
+{
+    let coordinator = match machines.first() {
+        Some(machine) => machine.io().coordinator().clone(),
+        None => {
+            return Summary {
+                machines: Vec::new(),
+                deadlocked: false,
+            }
+        }
+    };
+    let machines = thread::scope(|scope| {
+        let handles = machines
+            .into_iter()
+            .map(|mut machine| {
+                scope.spawn(move || {
+                    let stopped = machine.run();
+                    Report {
+                        sent: machine.io().sent(),
+                        received: machine.io().received(),
+                        stopped,
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("machine thread panicked"))
+            .collect()
+    });
+    Summary {
+        machines,
+        deadlocked: coordinator.is_deadlocked(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Op::*;
+    use ValueHolder::*;
+
+    #[test]
+    fn test_ping_pong() {
+        // each machine echoes back what it receives, decremented, until it's not positive
+        let program = [
+            Jgz(Register('p'), Number(2)),
+            Snd(Number(5)),
+            Rcv('a'),
+            Sub('a', Number(1)),
+            Snd(Register('a')),
+            Jgz(Register('a'), Number(-3)),
+        ];
+        let machines = ChannelIo::network(&[vec![1], vec![0]])
+            .into_iter()
+            .enumerate()
+            .map(|(id, io)| Machine::with_io(&program, io).with_register('p', id as i64))
+            .collect();
+        let summary = run(machines);
+        assert!(!summary.deadlocked);
+        let traffic = summary
+            .machines
+            .iter()
+            .map(|report| (report.sent, report.received, report.stopped))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            traffic,
+            vec![
+                (4, 3, MachineError::IPOutOfBounds),
+                (3, 3, MachineError::IPOutOfBounds),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deadlock() {
+        let program = [Snd(Register('p')), Rcv('a'), Rcv('a')];
+        let machines = ChannelIo::network(&[vec![1], vec![0]])
+            .into_iter()
+            .map(|io| Machine::with_io(&program, io))
+            .collect();
+        let summary = run(machines);
+        assert!(summary.deadlocked);
+        for report in summary.machines {
+            assert_eq!(
+                report,
+                Report {
+                    sent: 1,
+                    received: 1,
+                    stopped: MachineError::ReadBlock,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_snapshot_inbox() {
+        let program = [Rcv('a'), Rcv('b')];
+        let mut ios = ChannelIo::network(&[vec![1], vec![0]]);
+        let mut machine = Machine::with_io(&program, ios.pop().unwrap());
+        let mut sender = ios.pop().unwrap();
+        sender.send(4);
+        sender.send(9);
+
+        let snapshot = machine.snapshot();
+        assert_eq!(snapshot.inbox, vec![4, 9]);
+        // taking a snapshot doesn't consume the messages
+        assert_eq!(machine.io().inbox(), vec![4, 9]);
+
+        sender.send(1);
+        let mut restored = snapshot.clone();
+        restored.inbox = vec![7];
+        machine.restore(&restored);
+        assert_eq!(machine.io().inbox(), vec![7]);
+
+        // once the sender halts, the machine deadlocks after its one message
+        drop(sender);
+        assert_eq!(machine.run(), MachineError::ReadBlock);
+        assert_eq!(machine.register('a'), 7);
+        assert_eq!(machine.io().received(), 1);
+        assert!(machine.io().coordinator().is_deadlocked());
+    }
+}
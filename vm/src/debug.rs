@@ -0,0 +1,199 @@
+//! Inspect a running `Machine`: single steps, breakpoints, and traces
+
+use std::cmp::Ordering;
+
+use {Instruction, Io, Machine, MachineError, RegisterID, RegisterValue, Registers};
+
+/// A register whose value was changed by an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Delta {
+    pub register: RegisterID,
+    pub before: RegisterValue,
+    pub after: RegisterValue,
+}
+
+/// Every register whose value differs between two register files
+fn diff(before: &Registers, after: &Registers) -> Vec<Delta> {
+    before
+        .iter()
+        .zip(after.iter())
+        .filter(|&((_, before), (_, after))| before != after)
+        .map(|((register, before), (_, after))| Delta {
+            register,
+            before,
+            after,
+        })
+        .collect()
+}
+
+/// A single executed instruction, and its effect on the registers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step<I> {
+    /// The index of the instruction executed
+    pub ip: usize,
+    pub instruction: I,
+    pub delta: Vec<Delta>,
+}
+
+/// A condition on which `Machine::run_to` stops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Stop before executing the instruction at this index
+    At(usize),
+    /// Stop as soon as the comparison `register <ordering> value` holds
+    Register(RegisterID, Ordering, RegisterValue),
+}
+
+impl Breakpoint {
+    fn is_hit<I: Instruction, IO: Io>(&self, machine: &Machine<I, IO>) -> bool {
+        match *self {
+            Breakpoint::At(index) => machine.instruction_pointer() == index as RegisterValue,
+            Breakpoint::Register(id, ordering, value) => {
+                machine.register(id).cmp(&value) == ordering
+            }
+        }
+    }
+}
+
+/// Why `Machine::run_to` stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// The breakpoint at this index of the list given was hit
+    Breakpoint(usize),
+    /// The machine couldn't continue
+    Error(MachineError),
+}
+
+impl<'instructions, I: Instruction, IO: Io> Machine<'instructions, I, IO> {
+    /// Execute a single instruction, recording its effects
+    pub fn step(&mut self) -> Result<Step<I>, MachineError> {
+        let ip = self.instruction_pointer as usize;
+        let before = self.registers;
+        let (instruction, _) = self.execute()?;
+        Ok(Step {
+            ip,
+            instruction,
+            delta: diff(&before, &self.registers),
+        })
+    }
+
+    /// Execute instructions until a breakpoint is hit or the machine halts or blocks
+    ///
+    /// At least one instruction is executed, so that it's possible to continue
+    /// from a breakpoint by calling this again.
+    pub fn run_to(&mut self, breakpoints: &[Breakpoint]) -> Stop {
+        loop {
+            if let Err(err) = self.execute() {
+                return Stop::Error(err);
+            }
+            if let Some(index) = breakpoints.iter().position(|bp| bp.is_hit(self)) {
+                return Stop::Breakpoint(index);
+            }
+        }
+    }
+
+    /// Iterate over each step the machine takes until it halts or blocks
+    pub fn trace(&mut self) -> Trace<'_, 'instructions, I, IO> {
+        Trace {
+            machine: self,
+            stopped: None,
+        }
+    }
+}
+
+/// An iterator over the steps a machine executes
+pub struct Trace<'machine, 'instructions: 'machine, I: 'instructions, IO: 'machine> {
+    machine: &'machine mut Machine<'instructions, I, IO>,
+    stopped: Option<MachineError>,
+}
+
+impl<'machine, 'instructions, I: Instruction, IO: Io> Trace<'machine, 'instructions, I, IO> {
+    /// Why the trace ended, once it has
+    pub fn stopped(&self) -> Option<MachineError> {
+        self.stopped
+    }
+}
+
+impl<'machine, 'instructions, I: Instruction, IO: Io> Iterator
+    for Trace<'machine, 'instructions, I, IO>
+{
+    type Item = Step<I>;
+
+    fn next(&mut self) -> Option<Step<I>> {
+        if self.stopped.is_some() {
+            return None;
+        }
+        match self.machine.step() {
+            Ok(step) => Some(step),
+            Err(err) => {
+                self.stopped = Some(err);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Op;
+    use Op::*;
+    use ValueHolder::*;
+
+    /// multiply 3 by 2 by repeated addition
+    const PROGRAM: [Op; 4] = [
+        Set('a', Number(2)),
+        Add('b', Number(3)),
+        Sub('a', Number(1)),
+        Jnz(Register('a'), Number(-2)),
+    ];
+
+    #[test]
+    fn test_trace() {
+        let mut machine = Machine::new(&PROGRAM);
+        let steps = machine.trace().collect::<Vec<_>>();
+        assert_eq!(steps.len(), 7);
+        assert_eq!(
+            steps[1],
+            Step {
+                ip: 1,
+                instruction: Add('b', Number(3)),
+                delta: vec![Delta {
+                    register: 'b',
+                    before: 0,
+                    after: 3,
+                }],
+            }
+        );
+        assert!(steps[3].delta.is_empty());
+        assert_eq!(machine.register('b'), 6);
+    }
+
+    #[test]
+    fn test_trace_stopped() {
+        let mut machine = Machine::new(&PROGRAM);
+        let mut trace = machine.trace();
+        assert_eq!(trace.by_ref().count(), 7);
+        assert_eq!(trace.stopped(), Some(MachineError::IPOutOfBounds));
+    }
+
+    #[test]
+    fn test_run_to() {
+        let mut machine = Machine::new(&PROGRAM);
+        let breakpoints = [
+            Breakpoint::Register('b', Ordering::Greater, 4),
+            Breakpoint::At(3),
+        ];
+        assert_eq!(machine.run_to(&breakpoints), Stop::Breakpoint(1));
+        assert_eq!(machine.register('a'), 1);
+        assert_eq!(machine.run_to(&breakpoints), Stop::Breakpoint(0));
+        assert_eq!(machine.instruction_pointer(), 2);
+        assert_eq!(machine.run_to(&breakpoints), Stop::Breakpoint(0));
+        assert_eq!(machine.instruction_pointer(), 3);
+        machine.execute().unwrap();
+        assert_eq!(
+            machine.run_to(&[]),
+            Stop::Error(MachineError::IPOutOfBounds)
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! How machines send and receive messages
+
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::sync::RwLock;
+
+use RegisterValue;
+
+pub trait Io {
+    fn send(&mut self, message: RegisterValue);
+
+    /// Take the next message, or `None` if there is nothing to receive yet
+    fn receive(&mut self) -> Option<RegisterValue>;
+
+    /// The messages waiting to be received, for snapshots
+    ///
+    /// Io which can't inspect its pending messages reports none.
+    fn inbox(&self) -> Vec<RegisterValue> {
+        Vec::new()
+    }
+
+    /// Replace the messages waiting to be received, when restoring a snapshot
+    fn restore_inbox(&mut self, _messages: &[RegisterValue]) {}
+}
+
+/// Io for machines which never communicate
+///
+/// Sent messages are discarded, and receiving always blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NoIo;
+
+impl Io for NoIo {
+    fn send(&mut self, _: RegisterValue) {}
+
+    fn receive(&mut self) -> Option<RegisterValue> {
+        None
+    }
+}
+
+pub type Queue = Rc<RwLock<VecDeque<RegisterValue>>>;
+
+/// Io over shared queues, for machines running in lockstep on a single thread
+pub struct QueueIo {
+    send_q: Queue,
+    recv_q: Queue,
+}
+
+impl QueueIo {
+    pub fn new(send_q: Queue, recv_q: Queue) -> QueueIo {
+        QueueIo { send_q, recv_q }
+    }
+
+    pub fn send_queue(&self) -> &Queue {
+        &self.send_q
+    }
+
+    pub fn recv_queue(&self) -> &Queue {
+        &self.recv_q
+    }
+}
+
+impl Io for QueueIo {
+    fn send(&mut self, message: RegisterValue) {
+        self.send_q
+            .write()
+            .expect("couldn't get send lock")
+            .push_back(message);
+    }
+
+    fn receive(&mut self) -> Option<RegisterValue> {
+        self.recv_q
+            .write()
+            .expect("couldn't get receive lock")
+            .pop_front()
+    }
+
+    fn inbox(&self) -> Vec<RegisterValue> {
+        self.recv_q
+            .read()
+            .expect("couldn't get receive lock")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    fn restore_inbox(&mut self, messages: &[RegisterValue]) {
+        let mut recv_q = self.recv_q.write().expect("couldn't get receive lock");
+        recv_q.clear();
+        recv_q.extend(messages);
+    }
+}
@@ -0,0 +1,422 @@
+//! A small register machine shared by the day18 and day23 assembly puzzles
+//!
+//! Each puzzle defines its own instruction set: an enum implementing `Instruction`,
+//! which lowers every instruction to one of the core `Op`s. The `Machine` compiles
+//! those `Op`s to `bytecode` over a flat register array, and only ever executes that,
+//! so its semantics are defined in exactly one place.
+//!
+//! Communication between machines (`snd`/`rcv`) goes through the `Io` trait;
+//! the `channel` module runs networks of machines on their own threads.
+//! The `debug` module adds single-stepping, breakpoints, and tracing; `profile` counts executions.
+//! The `asm` module prints programs back out as text and reads them back in, and
+//! `snapshot` saves machine state. With the `proptest` feature, `strategy` generates operands.
+//! Optimizers may emit the loop-summarizing `Macro` operations from the `macros` module.
+
+use std::error::Error;
+use std::fmt::{self, Debug};
+use std::ops::{Index, IndexMut};
+
+#[cfg(feature = "proptest")]
+extern crate proptest;
+
+pub mod arithmetic;
+pub mod asm;
+pub mod bytecode;
+pub mod channel;
+pub mod debug;
+pub mod io;
+pub mod macros;
+pub mod profile;
+pub mod snapshot;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+pub use arithmetic::Arithmetic;
+use arithmetic::Wide;
+pub use asm::{disassemble, parse_lines, resolve_labels, LabelError, ParseLine};
+use bytecode::{Code, Target};
+pub use channel::ChannelIo;
+pub use debug::{Breakpoint, Delta, Step, Stop, Trace};
+pub use io::{Io, NoIo, Queue, QueueIo};
+pub use macros::Macro;
+pub use profile::Profile;
+pub use snapshot::Snapshot;
+
+pub type RegisterID = char;
+pub type RegisterValue = i64;
+
+/// The register file: one register for each of `a..=z`, all initially 0
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Registers([RegisterValue; 26]);
+
+impl Registers {
+    pub fn new() -> Registers {
+        Registers::default()
+    }
+
+    /// The array index of a register
+    ///
+    /// Panics if the register is outside `a..=z`.
+    pub fn slot(id: RegisterID) -> usize {
+        assert!(id.is_ascii_lowercase(), "no such register: {:?}", id);
+        (id as u8 - b'a') as usize
+    }
+
+    pub fn get(&self, id: RegisterID) -> RegisterValue {
+        self.0[Registers::slot(id)]
+    }
+
+    pub fn set(&mut self, id: RegisterID, value: RegisterValue) {
+        self.0[Registers::slot(id)] = value;
+    }
+
+    /// Every register and its value, in order
+    pub fn iter(&self) -> impl Iterator<Item = (RegisterID, RegisterValue)> + '_ {
+        (b'a'..=b'z').map(char::from).zip(self.0.iter().cloned())
+    }
+}
+
+impl Index<usize> for Registers {
+    type Output = RegisterValue;
+    fn index(&self, slot: usize) -> &RegisterValue {
+        &self.0[slot]
+    }
+}
+
+impl IndexMut<usize> for Registers {
+    fn index_mut(&mut self, slot: usize) -> &mut RegisterValue {
+        &mut self.0[slot]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueHolder {
+    Register(RegisterID),
+    Number(RegisterValue),
+}
+
+impl ValueHolder {
+    fn value(&self, registers: &Registers) -> RegisterValue {
+        use ValueHolder::*;
+        match *self {
+            Register(id) => registers.get(id),
+            Number(value) => value,
+        }
+    }
+}
+
+/// The core operations which every instruction set lowers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Set(RegisterID, ValueHolder),
+    Add(RegisterID, ValueHolder),
+    Sub(RegisterID, ValueHolder),
+    Mul(RegisterID, ValueHolder),
+    Mod(RegisterID, ValueHolder),
+    Snd(ValueHolder),
+    Rcv(RegisterID),
+    /// Jump by the second operand if the first is greater than zero
+    Jgz(ValueHolder, ValueHolder),
+    /// Jump by the second operand if the first is not zero
+    Jnz(ValueHolder, ValueHolder),
+    /// Summarize a whole loop, then skip past it
+    Macro(Macro),
+}
+
+/// An instruction set which the `Machine` can execute
+pub trait Instruction: Copy + Debug {
+    /// The core operation which this instruction performs
+    fn op(&self) -> Op;
+}
+
+impl Instruction for Op {
+    fn op(&self) -> Op {
+        *self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachineError {
+    IPOutOfBounds,
+    ReadBlock,
+    /// The instruction at this index overflowed a register, under checked arithmetic
+    Overflow {
+        ip: usize,
+    },
+    /// The instruction at this index took a remainder by zero
+    DivideByZero {
+        ip: usize,
+    },
+}
+
+impl fmt::Display for MachineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MachineError::IPOutOfBounds => write!(f, "instruction pointer out of bounds"),
+            MachineError::ReadBlock => write!(f, "blocked on an empty queue"),
+            MachineError::Overflow { ip } => write!(f, "overflow at instruction {}", ip),
+            MachineError::DivideByZero { ip } => write!(f, "divide by zero at instruction {}", ip),
+        }
+    }
+}
+
+impl Error for MachineError {}
+
+pub struct Machine<'instructions, I: 'instructions, IO = NoIo> {
+    instructions: &'instructions [I],
+    code: Vec<Code>,
+    instruction_pointer: RegisterValue,
+    registers: Registers,
+    arithmetic: Arithmetic,
+    io: IO,
+}
+
+impl<'instructions, I: Instruction> Machine<'instructions, I, NoIo> {
+    /// A machine which can't send or receive messages
+    pub fn new(instructions: &'instructions [I]) -> Machine<'instructions, I, NoIo> {
+        Machine::with_io(instructions, NoIo)
+    }
+}
+
+/// The next instruction pointer and the offset jumped
+fn jump_if(
+    ip: RegisterValue,
+    condition: bool,
+    target: Target,
+    registers: &Registers,
+) -> (RegisterValue, RegisterValue) {
+    match (condition, target) {
+        (false, _) => (ip + 1, 1),
+        (true, Target::Absolute(target)) => (target, target - ip),
+        (true, Target::Relative(offset)) => {
+            // a jump far enough to overflow is out of bounds either way
+            let offset = offset.value(registers);
+            (ip.saturating_add(offset), offset)
+        }
+    }
+}
+
+impl<'instructions, I: Instruction, IO: Io> Machine<'instructions, I, IO> {
+    /// Panics if an instruction names a register outside `a..=z`.
+    pub fn with_io(instructions: &'instructions [I], io: IO) -> Machine<'instructions, I, IO> {
+        Machine {
+            instructions,
+            code: bytecode::compile(instructions),
+            instruction_pointer: 0,
+            registers: Registers::new(),
+            arithmetic: Arithmetic::default(),
+            io,
+        }
+    }
+
+    /// Initialize a register before running the machine
+    pub fn with_register(mut self, id: RegisterID, value: RegisterValue) -> Self {
+        self.registers.set(id, value);
+        self
+    }
+
+    /// Change a register, such as when forking a run from a snapshot
+    pub fn set_register(&mut self, id: RegisterID, value: RegisterValue) {
+        self.registers.set(id, value);
+    }
+
+    /// Choose how overflowing results are handled; by default, they're errors
+    pub fn with_arithmetic(mut self, arithmetic: Arithmetic) -> Self {
+        self.arithmetic = arithmetic;
+        self
+    }
+
+    pub fn arithmetic(&self) -> Arithmetic {
+        self.arithmetic
+    }
+
+    pub fn instructions(&self) -> &'instructions [I] {
+        self.instructions
+    }
+
+    pub fn instruction_pointer(&self) -> RegisterValue {
+        self.instruction_pointer
+    }
+
+    /// The instruction which will be executed next, if the instruction pointer is in bounds
+    pub fn current(&self) -> Option<&'instructions I> {
+        if self.instruction_pointer < 0 {
+            return None;
+        }
+        self.instructions.get(self.instruction_pointer as usize)
+    }
+
+    pub fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    /// The value of a single register; unset registers are 0
+    pub fn register(&self, id: RegisterID) -> RegisterValue {
+        self.registers.get(id)
+    }
+
+    pub fn io(&self) -> &IO {
+        &self.io
+    }
+
+    pub fn io_mut(&mut self) -> &mut IO {
+        &mut self.io
+    }
+
+    /// Execute a single instruction
+    ///
+    /// If the instruction fails, the machine is unchanged: the instruction pointer
+    /// still indicates the failing instruction.
+    ///
+    /// Returns the instruction executed and the value it operated with:
+    /// the operand, the value sent or received, or the jump offset taken.
+    /// Macro-operations return the number of instructions they skip.
+    pub fn execute(&mut self) -> Result<(I, RegisterValue), MachineError> {
+        let instruction = *self.current().ok_or(MachineError::IPOutOfBounds)?;
+        let ip = self.instruction_pointer;
+        let registers = &mut self.registers;
+        let arithmetic = self.arithmetic;
+        let overflow = MachineError::Overflow { ip: ip as usize };
+        let narrow = |exact: Wide| arithmetic.narrow(exact).ok_or(overflow);
+        let (next, value) = match self.code[ip as usize] {
+            Code::Set(x, y) => {
+                let value = y.value(registers);
+                registers[x] = value;
+                (ip + 1, value)
+            }
+            Code::Add(x, y) => {
+                let value = y.value(registers);
+                registers[x] = narrow(registers[x] as Wide + value as Wide)?;
+                (ip + 1, value)
+            }
+            Code::Sub(x, y) => {
+                let value = y.value(registers);
+                registers[x] = narrow(registers[x] as Wide - value as Wide)?;
+                (ip + 1, value)
+            }
+            Code::Mul(x, y) => {
+                let value = y.value(registers);
+                registers[x] = narrow(registers[x] as Wide * value as Wide)?;
+                (ip + 1, value)
+            }
+            Code::Mod(x, y) => {
+                let value = y.value(registers);
+                if value == 0 {
+                    return Err(MachineError::DivideByZero { ip: ip as usize });
+                }
+                registers[x] = narrow(registers[x] as Wide % value as Wide)?;
+                (ip + 1, value)
+            }
+            Code::Snd(x) => {
+                let message = x.value(registers);
+                self.io.send(message);
+                (ip + 1, message)
+            }
+            Code::Rcv(x) => {
+                let message = self.io.receive().ok_or(MachineError::ReadBlock)?;
+                registers[x] = message;
+                (ip + 1, message)
+            }
+            Code::Jgz(x, target) => jump_if(ip, x.value(registers) > 0, target, registers),
+            Code::Jnz(x, target) => jump_if(ip, x.value(registers) != 0, target, registers),
+            Code::Macro(m) => {
+                m.execute(registers, arithmetic).ok_or(overflow)?;
+                let span = m.span() as RegisterValue;
+                (ip + span, span)
+            }
+        };
+        self.instruction_pointer = next;
+        Ok((instruction, value))
+    }
+
+    /// Execute instructions until the machine halts or blocks
+    pub fn run(&mut self) -> MachineError {
+        loop {
+            if let Err(err) = self.execute() {
+                return err;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::rc::Rc;
+    use std::sync::RwLock;
+
+    use Op::*;
+    use ValueHolder::*;
+
+    #[test]
+    fn test_countdown() {
+        // multiply 3 by 5 by repeated addition
+        let program = [
+            Set('a', Number(5)),
+            Add('b', Number(3)),
+            Sub('a', Number(1)),
+            Jnz(Register('a'), Number(-2)),
+        ];
+        let mut machine = Machine::new(&program);
+        assert_eq!(machine.run(), MachineError::IPOutOfBounds);
+        assert_eq!(machine.register('b'), 15);
+        assert_eq!(machine.register('a'), 0);
+    }
+
+    #[test]
+    fn test_jgz_ignores_negative() {
+        let program = [
+            Set('a', Number(-1)),
+            Jgz(Register('a'), Number(2)),
+            Mod('b', Number(1)),
+        ];
+        let mut machine = Machine::new(&program).with_register('b', 7);
+        machine.run();
+        assert_eq!(machine.register('b'), 0);
+    }
+
+    #[test]
+    fn test_arithmetic_policy() {
+        let program = [Set('a', Number(RegisterValue::MAX)), Add('a', Number(2))];
+        let mut machine = Machine::new(&program);
+        assert_eq!(machine.run(), MachineError::Overflow { ip: 1 });
+        assert_eq!(machine.instruction_pointer(), 1);
+        assert_eq!(machine.register('a'), RegisterValue::MAX);
+
+        let mut machine = Machine::new(&program).with_arithmetic(Arithmetic::Wrapping);
+        machine.run();
+        assert_eq!(machine.register('a'), RegisterValue::MIN + 1);
+
+        let mut machine = Machine::new(&program).with_arithmetic(Arithmetic::Saturating);
+        machine.run();
+        assert_eq!(machine.register('a'), RegisterValue::MAX);
+    }
+
+    #[test]
+    fn test_divide_by_zero() {
+        let program = [Set('a', Number(3)), Mod('a', Register('b'))];
+        let mut machine = Machine::new(&program).with_arithmetic(Arithmetic::Wrapping);
+        assert_eq!(machine.run(), MachineError::DivideByZero { ip: 1 });
+        assert_eq!(
+            MachineError::DivideByZero { ip: 1 }.to_string(),
+            "divide by zero at instruction 1"
+        );
+    }
+
+    #[test]
+    fn test_queue_io() {
+        let queue = Rc::new(RwLock::new(VecDeque::new()));
+        let program = [
+            Snd(Number(4)),
+            Snd(Register('p')),
+            Rcv('a'),
+            Rcv('b'),
+            Rcv('c'),
+        ];
+        let mut machine = Machine::with_io(&program, QueueIo::new(queue.clone(), queue.clone()))
+            .with_register('p', 9);
+        assert_eq!(machine.run(), MachineError::ReadBlock);
+        assert_eq!(machine.instruction_pointer(), 4);
+        assert_eq!((machine.register('a'), machine.register('b')), (4, 9));
+    }
+}
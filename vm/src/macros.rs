@@ -0,0 +1,323 @@
+//! Macro-operations, which summarize entire loops in a single step
+//!
+//! Optimizers produce them by recognizing idioms, though they can also be written by hand.
+//! A macro-operation replaces the first instruction of the loop it summarizes.
+//! Executing it has exactly the effect of running the loop from that point until
+//! control leaves it, then continues `span` instructions later, just past the loop.
+//! The rest of the loop stays in place, so no jump offsets need to change.
+//!
+//! That holds only for loops which finish without overflowing. When the loop
+//! would overflow a register, or never reach its bound, the macro stops the
+//! machine with `MachineError::Overflow` instead, before changing any register,
+//! except where noted. It doesn't summarize wrapped or saturated loops.
+
+use std::fmt;
+
+use arithmetic::{Arithmetic, Wide};
+use {RegisterID, RegisterValue, Registers, ValueHolder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Macro {
+    /// `x -= y * counter; counter = 0`
+    ///
+    /// Summarizes `sub x y; sub counter 1; jnz counter -2`. If `counter` isn't positive,
+    /// that loop only ends by wrapping around, so only wrapping arithmetic completes it;
+    /// that's also the one case in which this summarizes wrapped arithmetic.
+    MulSub {
+        x: RegisterID,
+        y: ValueHolder,
+        counter: RegisterID,
+        span: usize,
+    },
+    /// Clear `flag` if `d * e' == b` for any `e'` in `e..b`; then `e = b; scratch = 0`
+    ///
+    /// Summarizes a loop testing whether `d` divides `b` by trying every cofactor.
+    /// The loop always runs at least once, so it needs `e < b`.
+    ClearIfFactor {
+        flag: RegisterID,
+        d: RegisterID,
+        e: RegisterID,
+        b: ValueHolder,
+        scratch: RegisterID,
+        span: usize,
+    },
+    /// Clear `flag` if `d' * e' == b` for any `d'` in `d..b` and `e'` in `e_start..b`;
+    /// then `d = b; e = b; scratch = 0`
+    ///
+    /// Summarizes a `ClearIfFactor` loop nested in a loop over `d`:
+    /// a test of whether `b` is composite. It needs `d < b` and `e_start < b`.
+    ClearIfComposite {
+        flag: RegisterID,
+        d: RegisterID,
+        e: RegisterID,
+        e_start: RegisterValue,
+        b: ValueHolder,
+        scratch: RegisterID,
+        span: usize,
+    },
+}
+
+/// Every pair of integers whose product is `n`, which must not be 0
+fn factor_pairs(n: RegisterValue) -> Vec<(RegisterValue, RegisterValue)> {
+    let magnitude = n.abs();
+    let mut pairs = Vec::new();
+    let mut k = 1;
+    while k <= magnitude / k {
+        if magnitude % k == 0 {
+            for &x in &[k, magnitude / k] {
+                pairs.push((x, n / x));
+                pairs.push((-x, -(n / x)));
+            }
+        }
+        k += 1;
+    }
+    pairs
+}
+
+/// Whether a loop trying every `d'` in `ds` and `e'` in `es` stays in range
+///
+/// Both ranges are inclusive and non-empty. Each iteration computes `d' * e' - b`
+/// and steps its counter by 1 up to `b`, comparing `counter - b`. The products are
+/// bilinear, so their extremes are at the corners.
+fn loop_fits(
+    ds: (RegisterValue, RegisterValue),
+    es: (RegisterValue, RegisterValue),
+    b: RegisterValue,
+) -> bool {
+    let fits = |value: Wide| Arithmetic::Checked.narrow(value).is_some();
+    let b = b as Wide;
+    let corners = [(ds.0, es.0), (ds.0, es.1), (ds.1, es.0), (ds.1, es.1)];
+    corners.iter().all(|&(d, e)| {
+        let product = d as Wide * e as Wide;
+        fits(product) && fits(product - b)
+    }) && fits(ds.0 as Wide + 1 - b)
+        && fits(es.0 as Wide + 1 - b)
+}
+
+impl Macro {
+    /// The number of instructions this summarizes, beginning with its own
+    pub fn span(&self) -> usize {
+        match *self {
+            Macro::MulSub { span, .. }
+            | Macro::ClearIfFactor { span, .. }
+            | Macro::ClearIfComposite { span, .. } => span,
+        }
+    }
+
+    /// The name of this macro in assembly text
+    pub fn mnemonic(&self) -> &'static str {
+        match *self {
+            Macro::MulSub { .. } => "mulsub",
+            Macro::ClearIfFactor { .. } => "clrfactor",
+            Macro::ClearIfComposite { .. } => "clrcomposite",
+        }
+    }
+
+    /// Apply this macro's effects to the registers, or `None` if that overflows
+    pub(crate) fn execute(&self, registers: &mut Registers, arithmetic: Arithmetic) -> Option<()> {
+        match *self {
+            Macro::MulSub { x, y, counter, .. } => {
+                let y = y.value(registers);
+                let n = registers.get(counter);
+                if n <= 0 && arithmetic != Arithmetic::Wrapping {
+                    return None;
+                }
+                let value = arithmetic.narrow(registers.get(x) as Wide - y as Wide * n as Wide)?;
+                registers.set(x, value);
+                registers.set(counter, 0);
+            }
+            Macro::ClearIfFactor {
+                flag,
+                d,
+                e,
+                b,
+                scratch,
+                ..
+            } => {
+                let b = b.value(registers);
+                let d = registers.get(d);
+                let e_start = registers.get(e);
+                if e_start >= b || !loop_fits((d, d), (e_start, b - 1), b) {
+                    return None;
+                }
+                let found = if d == 0 {
+                    b == 0 && e_start < b
+                } else {
+                    b.checked_rem(d) == Some(0)
+                        && b.checked_div(d).is_some_and(|q| e_start <= q && q < b)
+                };
+                if found {
+                    registers.set(flag, 0);
+                }
+                registers.set(e, b);
+                registers.set(scratch, 0);
+            }
+            Macro::ClearIfComposite {
+                flag,
+                d,
+                e,
+                e_start,
+                b,
+                scratch,
+                ..
+            } => {
+                let b = b.value(registers);
+                let d_start = registers.get(d);
+                if d_start >= b || e_start >= b || !loop_fits((d_start, b - 1), (e_start, b - 1), b)
+                {
+                    return None;
+                }
+                // with both factors below `b`, a product of `b` needs it to be positive
+                let found = b > 0
+                    && factor_pairs(b)
+                        .into_iter()
+                        .any(|(x, y)| d_start <= x && x < b && e_start <= y && y < b);
+                if found {
+                    registers.set(flag, 0);
+                }
+                registers.set(d, b);
+                registers.set(e, b);
+                registers.set(scratch, 0);
+            }
+        }
+        Some(())
+    }
+}
+
+impl fmt::Display for Macro {
+    /// Macros print as pseudo-instructions, with every operand and the span explicit
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Macro::MulSub {
+                x,
+                y,
+                counter,
+                span,
+            } => write!(f, "{} {} {} {} {}", self.mnemonic(), x, y, counter, span),
+            Macro::ClearIfFactor {
+                flag,
+                d,
+                e,
+                b,
+                scratch,
+                span,
+            } => write!(
+                f,
+                "{} {} {} {} {} {} {}",
+                self.mnemonic(),
+                flag,
+                d,
+                e,
+                b,
+                scratch,
+                span
+            ),
+            Macro::ClearIfComposite {
+                flag,
+                d,
+                e,
+                e_start,
+                b,
+                scratch,
+                span,
+            } => write!(
+                f,
+                "{} {} {} {} {} {} {} {}",
+                self.mnemonic(),
+                flag,
+                d,
+                e,
+                e_start,
+                b,
+                scratch,
+                span
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_past_bound_overflows() {
+        let mut registers = Registers::new();
+        registers.set('b', 10);
+        registers.set('d', 2);
+        registers.set('e', 10);
+        registers.set('f', 1);
+        let factor = Macro::ClearIfFactor {
+            flag: 'f',
+            d: 'd',
+            e: 'e',
+            b: ValueHolder::Register('b'),
+            scratch: 'g',
+            span: 9,
+        };
+        let before = registers;
+        for &arithmetic in &[
+            Arithmetic::Wrapping,
+            Arithmetic::Checked,
+            Arithmetic::Saturating,
+        ] {
+            assert_eq!(factor.execute(&mut registers, arithmetic), None);
+            assert_eq!(registers, before);
+        }
+
+        registers.set('e', 3);
+        assert_eq!(
+            factor.execute(&mut registers, Arithmetic::Checked),
+            Some(())
+        );
+        assert_eq!((registers.get('f'), registers.get('e')), (0, 10));
+
+        let composite = Macro::ClearIfComposite {
+            flag: 'f',
+            d: 'd',
+            e: 'e',
+            e_start: 2,
+            b: ValueHolder::Register('b'),
+            scratch: 'g',
+            span: 14,
+        };
+        registers.set('d', 11);
+        assert_eq!(composite.execute(&mut registers, Arithmetic::Checked), None);
+    }
+
+    #[test]
+    fn test_product_overflow() {
+        let mut registers = Registers::new();
+        registers.set('b', RegisterValue::MAX);
+        registers.set('d', 3);
+        registers.set('e', 2);
+        let factor = Macro::ClearIfFactor {
+            flag: 'f',
+            d: 'd',
+            e: 'e',
+            b: ValueHolder::Register('b'),
+            scratch: 'g',
+            span: 9,
+        };
+        assert_eq!(factor.execute(&mut registers, Arithmetic::Checked), None);
+    }
+
+    #[test]
+    fn test_factor_pairs() {
+        let mut pairs = factor_pairs(-6);
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            vec![
+                (-6, 1),
+                (-3, 2),
+                (-2, 3),
+                (-1, 6),
+                (1, -6),
+                (2, -3),
+                (3, -2),
+                (6, -1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Count how often each instruction runs, to find the loops worth optimizing
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use {Instruction, Io, Machine, MachineError, Op};
+
+/// Execution counts from a profiled run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// How many times each instruction was executed, by index
+    pub hits: Vec<u64>,
+    /// How many times each jump was taken; 0 for every other instruction
+    pub taken: Vec<u64>,
+    /// How many instructions were executed with each mnemonic
+    pub opcodes: BTreeMap<&'static str, u64>,
+}
+
+impl Profile {
+    /// How many times the instruction at this index was executed without jumping
+    pub fn not_taken(&self, ip: usize) -> u64 {
+        self.hits[ip] - self.taken[ip]
+    }
+
+    pub fn total(&self) -> u64 {
+        self.hits.iter().sum()
+    }
+
+    /// The indices of the `n` most executed instructions, hottest first
+    pub fn hot_spots(&self, n: usize) -> Vec<usize> {
+        let mut indices = (0..self.hits.len())
+            .filter(|&ip| self.hits[ip] > 0)
+            .collect::<Vec<_>>();
+        indices.sort_by_key(|&ip| (std::cmp::Reverse(self.hits[ip]), ip));
+        indices.truncate(n);
+        indices
+    }
+
+    /// A listing of the program annotated with execution counts
+    ///
+    /// Each line shows how often the instruction ran, its share of the total,
+    /// how often jumps were taken and not taken, and a bar scaled to the hottest
+    /// instruction. A summary by opcode follows.
+    pub fn annotate<I: Instruction>(&self, instructions: &[I]) -> String {
+        const BAR: u64 = 20;
+        let total = self.total().max(1);
+        let hottest = self.hits.iter().cloned().max().unwrap_or(0).max(1);
+
+        let texts = instructions
+            .iter()
+            .map(|instruction| instruction.op().to_string())
+            .collect::<Vec<_>>();
+        let width = texts.iter().map(String::len).max().unwrap_or(0).max(11);
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{:>4}  {:<width$} {:>12} {:>6}  {:>12} {:>12}",
+            "ip",
+            "instruction",
+            "hits",
+            "%",
+            "taken",
+            "not taken",
+            width = width,
+        )
+        .unwrap();
+        for (ip, (instruction, text)) in instructions.iter().zip(&texts).enumerate() {
+            let hits = self.hits[ip];
+            let (taken, not_taken) = match instruction.op() {
+                Op::Jgz(..) | Op::Jnz(..) => {
+                    (self.taken[ip].to_string(), self.not_taken(ip).to_string())
+                }
+                _ => (String::new(), String::new()),
+            };
+            let line = format!(
+                "{:>4}  {:<width$} {:>12} {:>6.2}  {:>12} {:>12}  {}",
+                ip,
+                text,
+                hits,
+                100.0 * hits as f64 / total as f64,
+                taken,
+                not_taken,
+                "#".repeat((hits * BAR).div_ceil(hottest) as usize),
+                width = width,
+            );
+            writeln!(out, "{}", line.trim_end()).unwrap();
+        }
+        writeln!(out).unwrap();
+        writeln!(out, "{:<12} {:>12}", "opcode", "hits").unwrap();
+        for (opcode, hits) in &self.opcodes {
+            writeln!(out, "{:<12} {:>12}", opcode, hits).unwrap();
+        }
+        out
+    }
+}
+
+impl<'instructions, I: Instruction, IO: Io> Machine<'instructions, I, IO> {
+    /// Execute instructions until the machine halts or blocks, counting each one
+    pub fn profile(&mut self) -> (Profile, MachineError) {
+        let len = self.instructions.len();
+        let mut hits = vec![0; len];
+        let mut taken = vec![0; len];
+        let err = loop {
+            let ip = self.instruction_pointer as usize;
+            let jumped = match self.current().map(|instruction| instruction.op()) {
+                Some(Op::Jgz(x, _)) => x.value(&self.registers) > 0,
+                Some(Op::Jnz(x, _)) => x.value(&self.registers) != 0,
+                _ => false,
+            };
+            if let Err(err) = self.execute() {
+                break err;
+            }
+            hits[ip] += 1;
+            if jumped {
+                taken[ip] += 1;
+            }
+        };
+
+        let mut opcodes = BTreeMap::new();
+        for (instruction, &count) in self.instructions.iter().zip(&hits) {
+            if count > 0 {
+                *opcodes.entry(instruction.op().mnemonic()).or_insert(0) += count;
+            }
+        }
+        let profile = Profile {
+            hits,
+            taken,
+            opcodes,
+        };
+        (profile, err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Op::*;
+    use ValueHolder::*;
+
+    /// multiply 3 by 2 by repeated addition
+    const PROGRAM: [Op; 4] = [
+        Set('a', Number(2)),
+        Add('b', Number(3)),
+        Sub('a', Number(1)),
+        Jnz(Register('a'), Number(-2)),
+    ];
+
+    #[test]
+    fn test_profile() {
+        let (profile, err) = Machine::new(&PROGRAM).profile();
+        assert_eq!(err, MachineError::IPOutOfBounds);
+        assert_eq!(profile.hits, vec![1, 2, 2, 2]);
+        assert_eq!(profile.taken, vec![0, 0, 0, 1]);
+        assert_eq!(profile.not_taken(3), 1);
+        assert_eq!(profile.opcodes["add"], 2);
+        assert_eq!(profile.hot_spots(2), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_annotate() {
+        let (profile, _) = Machine::new(&PROGRAM).profile();
+        let listing = profile.annotate(&PROGRAM);
+        let lines = listing.lines().collect::<Vec<_>>();
+        assert!(lines[1].starts_with("   0  set a 2"));
+        assert!(lines[1].ends_with("  ##########"));
+        assert!(lines[4].contains("jnz a -2"));
+        assert!(lines[4].ends_with("1            1  ####################"));
+        assert!(listing.contains("\nset                     1\n"));
+    }
+}
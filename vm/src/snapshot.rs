@@ -0,0 +1,235 @@
+//! Save a machine's state, to resume it later or fork new runs from it
+//!
+//! A snapshot holds everything a machine needs besides its program: the
+//! instruction pointer, the registers, the arithmetic policy, and any messages
+//! waiting to be received. It's written as text, one field per line:
+//!
+//! ```text
+//! ip 12
+//! arithmetic checked
+//! registers a=1 b=57 p=-3
+//! inbox 4 9
+//! ```
+//!
+//! Registers which are 0 are omitted. A file may hold several snapshots,
+//! such as one for each machine of a duet, separated by blank lines.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use {Arithmetic, Instruction, Io, Machine, RegisterValue, Registers};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub instruction_pointer: RegisterValue,
+    pub registers: Registers,
+    pub arithmetic: Arithmetic,
+    /// Messages sent to the machine which it hasn't yet received
+    pub inbox: Vec<RegisterValue>,
+}
+
+impl<'instructions, I: Instruction, IO: Io> Machine<'instructions, I, IO> {
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            instruction_pointer: self.instruction_pointer,
+            registers: self.registers,
+            arithmetic: self.arithmetic,
+            inbox: self.io.inbox(),
+        }
+    }
+
+    /// Return to the state in a snapshot, which may have come from a different machine
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.instruction_pointer = snapshot.instruction_pointer;
+        self.registers = snapshot.registers;
+        self.arithmetic = snapshot.arithmetic;
+        self.io.restore_inbox(&snapshot.inbox);
+    }
+}
+
+impl fmt::Display for Arithmetic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Arithmetic::Wrapping => "wrapping",
+            Arithmetic::Checked => "checked",
+            Arithmetic::Saturating => "saturating",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Arithmetic {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Arithmetic, String> {
+        match s {
+            "wrapping" => Ok(Arithmetic::Wrapping),
+            "checked" => Ok(Arithmetic::Checked),
+            "saturating" => Ok(Arithmetic::Saturating),
+            _ => Err(format!("unknown arithmetic policy {:?}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "ip {}", self.instruction_pointer)?;
+        writeln!(f, "arithmetic {}", self.arithmetic)?;
+        write!(f, "registers")?;
+        for (id, value) in self.registers.iter().filter(|&(_, value)| value != 0) {
+            write!(f, " {}={}", id, value)?;
+        }
+        writeln!(f)?;
+        write!(f, "inbox")?;
+        for message in &self.inbox {
+            write!(f, " {}", message)?;
+        }
+        writeln!(f)
+    }
+}
+
+fn parse_value(token: &str) -> Result<RegisterValue, String> {
+    token
+        .parse()
+        .map_err(|e| format!("bad value {:?}: {}", token, e))
+}
+
+impl FromStr for Snapshot {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Snapshot, String> {
+        let mut snapshot = Snapshot {
+            instruction_pointer: 0,
+            registers: Registers::new(),
+            arithmetic: Arithmetic::default(),
+            inbox: Vec::new(),
+        };
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let mut tokens = line.split_whitespace();
+            match tokens.next() {
+                Some("ip") => {
+                    let ip = tokens.next().ok_or("missing instruction pointer")?;
+                    snapshot.instruction_pointer = parse_value(ip)?;
+                }
+                Some("arithmetic") => {
+                    snapshot.arithmetic = tokens.next().ok_or("missing policy")?.parse()?;
+                }
+                Some("registers") => {
+                    for token in tokens.by_ref() {
+                        let (id, value) = token
+                            .split_once('=')
+                            .ok_or_else(|| format!("bad register {:?}", token))?;
+                        let mut chars = id.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(id), None) if id.is_ascii_lowercase() => {
+                                snapshot.registers.set(id, parse_value(value)?)
+                            }
+                            _ => return Err(format!("no such register {:?}", id)),
+                        }
+                    }
+                }
+                Some("inbox") => {
+                    snapshot.inbox = tokens.by_ref().map(parse_value).collect::<Result<_, _>>()?;
+                }
+                Some(field) => return Err(format!("unknown field {:?}", field)),
+                None => unreachable!("blank lines are skipped"),
+            }
+            if let Some(extra) = tokens.next() {
+                return Err(format!("unexpected {:?}", extra));
+            }
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Write snapshots to a file, replacing its contents
+pub fn save<P: AsRef<Path>>(path: P, snapshots: &[Snapshot]) -> io::Result<()> {
+    let text = snapshots
+        .iter()
+        .map(|snapshot| snapshot.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(path, text)
+}
+
+/// Read every snapshot from a file written by `save`
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Vec<Snapshot>> {
+    let text = fs::read_to_string(path)?;
+    text.split("\n\n")
+        .filter(|chunk| !chunk.trim().is_empty())
+        .map(|chunk| {
+            chunk
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::env;
+    use std::process;
+    use std::rc::Rc;
+    use std::sync::RwLock;
+
+    use Op::*;
+    use QueueIo;
+    use ValueHolder::*;
+
+    #[test]
+    fn test_text_round_trip() {
+        let mut registers = Registers::new();
+        registers.set('a', 1);
+        registers.set('p', -3);
+        let snapshot = Snapshot {
+            instruction_pointer: 12,
+            registers,
+            arithmetic: Arithmetic::Saturating,
+            inbox: vec![4, 9],
+        };
+        let text = snapshot.to_string();
+        assert_eq!(
+            text,
+            "ip 12\narithmetic saturating\nregisters a=1 p=-3\ninbox 4 9\n"
+        );
+        assert_eq!(text.parse(), Ok(snapshot));
+    }
+
+    #[test]
+    fn test_bad_snapshot() {
+        assert!("ip 1\nregisters A=2".parse::<Snapshot>().is_err());
+        assert!("ip".parse::<Snapshot>().is_err());
+        assert!("flags 3".parse::<Snapshot>().is_err());
+    }
+
+    #[test]
+    fn test_resume_from_file() {
+        let program = [
+            Rcv('a'),
+            Add('b', Register('a')),
+            Rcv('a'),
+            Add('b', Register('a')),
+        ];
+        let queue = Rc::new(RwLock::new(VecDeque::new()));
+        queue.write().unwrap().extend(&[3, 4]);
+        let mut machine = Machine::with_io(&program, QueueIo::new(queue.clone(), queue.clone()));
+        machine.execute().unwrap();
+        machine.execute().unwrap();
+
+        let path = env::temp_dir().join(format!("vm-snapshot-{}.txt", process::id()));
+        save(&path, &[machine.snapshot(), machine.snapshot()]).unwrap();
+        let snapshots = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].inbox, vec![4]);
+
+        let queue = Rc::new(RwLock::new(VecDeque::new()));
+        let mut resumed = Machine::with_io(&program, QueueIo::new(queue.clone(), queue));
+        resumed.restore(&snapshots[0]);
+        resumed.run();
+        assert_eq!(resumed.register('b'), 7);
+    }
+}
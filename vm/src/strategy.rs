@@ -0,0 +1,19 @@
+//! Proptest strategies for operands, shared by the puzzles' round-trip tests
+//!
+//! Only built with the `proptest` feature, which the puzzles enable for their tests.
+
+use proptest::prelude::*;
+
+use {RegisterID, RegisterValue, ValueHolder};
+
+pub fn register() -> impl Strategy<Value = RegisterID> {
+    (b'a'..=b'z').prop_map(char::from)
+}
+
+pub fn value() -> impl Strategy<Value = ValueHolder> {
+    prop_oneof![
+        register().prop_map(ValueHolder::Register),
+        (-20..20 as RegisterValue).prop_map(ValueHolder::Number),
+        any::<RegisterValue>().prop_map(ValueHolder::Number),
+    ]
+}